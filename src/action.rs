@@ -0,0 +1,105 @@
+//! キー入力をページごとの操作に変換する
+//!
+//! 何をするかは [`crate::app::App::apply_action`] が決めるので、キー割り当てを変えたり
+//! キー以外（CLI など）から同じ操作を送ったりできる。
+
+use crate::app::Page;
+use crate::keymap::{self, KeyBinding};
+use crossterm::event::{KeyCode, KeyEvent};
+use serde::Deserialize;
+
+/// config.json の keybindings では snake_case の名前で指定する（例: "toggle_playback"）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Action {
+    Quit,
+    ShowHelp,
+    /// 履歴を戻る / 進む（[ / ]）
+    HistoryBack,
+    HistoryForward,
+    /// 表示中のページのデータを取り直す
+    Refresh,
+    /// どのページからでも再生画面へ
+    OpenNowPlaying,
+
+    // リストの選択
+    MoveUp,
+    MoveDown,
+    PageUp,
+    PageDown,
+    First,
+    Last,
+    /// 選択中の項目を再生する・決定する
+    Select,
+    /// 選択中の項目の中身を開く
+    Open,
+    /// 前のページに戻る（入力中の操作があれば取り消す）
+    Back,
+
+    // ページの移動
+    OpenPlaylists,
+    OpenDevices,
+    OpenNewReleases,
+    OpenSearch,
+
+    // プレイリスト・アルバム
+    Enqueue,
+    Filter,
+    CreatePlaylist,
+    RetryPlaylists,
+    ToggleFollow,
+    ToggleSave,
+    RemoveTrack,
+
+    // 再生
+    Previous,
+    Next,
+    TogglePlayback,
+    VolumeUp,
+    VolumeDown,
+    /// 曲の n/10 の位置へ
+    SeekTenths(u8),
+    Restart,
+    Resume,
+    ShowArtists,
+    ShowTrackInfo,
+    CopyLink,
+    OpenInBrowser,
+    AddToPlaylist,
+    /// スリープタイマーを始める（動いていれば止める）
+    SleepTimer,
+
+    // デバイス
+    Reload,
+
+    // キューの確認
+    MoveTrackUp,
+    MoveTrackDown,
+    ToggleInclude,
+}
+
+impl Action {
+    /// ページで有効なキーなら対応する操作（文字入力中のキーは呼び出し側で先に処理する）
+    ///
+    /// 割り当ては [`keymap`] の表から引くので、ヘルプの表示と食い違わない。
+    pub fn from_key(page: Page, key_event: KeyEvent) -> Option<Self> {
+        // 検索ページでは文字はすべて入力に使う（j/k での移動も無し、Ctrl 付きだけ操作にする）
+        let typing = page == Page::Search && matches!(key_event.code, KeyCode::Char(_));
+        // どのページでも有効なキーを先に見る
+        keymap::GLOBAL
+            .iter()
+            .chain(keymap::bindings_for(page))
+            .flat_map(KeyBinding::key_actions)
+            .find(|key| key.matches(key_event) && (key.ctrl || !typing))
+            .map(|key| key.action)
+    }
+}
+
+/// リスト共通の移動キー（↑↓ / jk / PageUp / PageDown / Home / End）
+pub fn list_action(code: KeyCode) -> Option<Action> {
+    keymap::LIST
+        .iter()
+        .flat_map(KeyBinding::key_actions)
+        .find(|key| key.code == code)
+        .map(|key| key.action)
+}
@@ -0,0 +1,102 @@
+use crate::api::spotify::{SpotifyClient, Track};
+use image::DynamicImage;
+use ratatui::{buffer::Buffer, layout::Rect, widgets::StatefulWidget};
+use ratatui_image::{
+    picker::Picker,
+    protocol::StatefulProtocol,
+    StatefulImage,
+};
+use tokio::task::JoinHandle;
+
+/// 端末からフォントの大きさを取れなかったときの 1 セルのピクセル数
+const FALLBACK_FONT_SIZE: (u16, u16) = (8, 16);
+
+/// 再生中の曲のジャケット画像
+///
+/// 端末が対応していれば kitty / sixel / iTerm2 の画像で、無ければハーフブロックの文字で描く。
+pub struct AlbumArt {
+    picker: Picker,
+    /// 表示中（または取得中）の画像の URL
+    url: Option<String>,
+    image: Option<Box<dyn StatefulProtocol>>,
+    task: Option<JoinHandle<Result<DynamicImage, String>>>,
+}
+
+/// 端末に問い合わせて使える画像の形式を選ぶ（stdin を読むので起動時の raw モード前に一度だけ）
+pub fn detect_picker() -> Picker {
+    #[cfg(unix)]
+    let mut picker = Picker::from_termios().unwrap_or_else(|_| Picker::new(FALLBACK_FONT_SIZE));
+    // termios が無いのでフォントの大きさは決め打ち
+    #[cfg(not(unix))]
+    let mut picker = Picker::new(FALLBACK_FONT_SIZE);
+    let protocol = picker.guess_protocol();
+    tracing::debug!(?protocol, "Detected image protocol");
+    picker
+}
+
+impl AlbumArt {
+    pub fn new(picker: Picker) -> Self {
+        Self {
+            picker,
+            url: None,
+            image: None,
+            task: None,
+        }
+    }
+
+    /// 曲が変わっていればその曲の画像を取り直す（無ければ消す）
+    pub fn update(&mut self, client: &SpotifyClient, track: Option<&Track>) {
+        let url = track.and_then(|track| image_url(track)).map(str::to_string);
+        if url == self.url {
+            return;
+        }
+        if let Some(task) = self.task.take() {
+            task.abort();
+        }
+        self.image = None;
+        self.url = url.clone();
+        let Some(url) = url else {
+            return;
+        };
+        let client = client.clone();
+        self.task = Some(tokio::spawn(async move {
+            client.download_image(&url).await.map_err(|e| e.to_string())
+        }));
+    }
+
+    /// 取得が終わっていれば描画できる形にする
+    pub async fn poll(&mut self) {
+        if !self.task.as_ref().is_some_and(JoinHandle::is_finished) {
+            return;
+        }
+        let Some(task) = self.task.take() else {
+            return;
+        };
+        match task.await {
+            // 描画先の大きさに合わせた縮小は描画時に行う
+            Ok(Ok(image)) => self.image = Some(self.picker.new_resize_protocol(image)),
+            Ok(Err(e)) => tracing::debug!(error = %e, "Failed to load album art"),
+            Err(_) => {}
+        }
+    }
+
+    pub fn render(&mut self, area: Rect, buf: &mut Buffer) {
+        if let Some(image) = self.image.as_mut() {
+            StatefulImage::new(None).render(area, buf, image);
+        }
+    }
+}
+
+impl Drop for AlbumArt {
+    fn drop(&mut self) {
+        if let Some(task) = self.task.take() {
+            task.abort();
+        }
+    }
+}
+
+/// 表示に使う画像（大きいものから並んでいるので、2 番目の 300px 程度を選ぶ）
+fn image_url(track: &Track) -> Option<&str> {
+    let images = &track.album.images;
+    images.get(1).or(images.first()).map(|image| image.url.as_str())
+}
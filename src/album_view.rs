@@ -0,0 +1,45 @@
+use crate::api::spotify::{Album, Track};
+use crate::utils::{move_selection, Movement};
+use ratatui::widgets::ListState;
+
+/// 新着から開いたアルバムの曲一覧
+#[derive(Debug)]
+pub struct AlbumView {
+    pub album: Album,
+    pub tracks: Vec<Track>,
+    pub state: ListState,
+    /// ライブラリに保存しているか（確認できなければ None）
+    pub saved: Option<bool>,
+}
+
+impl AlbumView {
+    pub fn new(album: Album, mut tracks: Vec<Track>) -> Self {
+        // アルバムの曲一覧には album が含まれないので補う（詳細表示などで使う）
+        for track in &mut tracks {
+            track.album = album.clone();
+        }
+        let mut state = ListState::default();
+        if !tracks.is_empty() {
+            state.select(Some(0));
+        }
+        Self {
+            album,
+            tracks,
+            state,
+            saved: None,
+        }
+    }
+
+    pub fn move_selection(&mut self, movement: Movement) {
+        let selected = move_selection(self.state.selected(), self.tracks.len(), movement);
+        self.state.select(selected);
+    }
+
+    pub fn selected_track(&self) -> Option<&Track> {
+        self.tracks.get(self.state.selected()?)
+    }
+
+    pub fn total_duration_ms(&self) -> i64 {
+        self.tracks.iter().map(|track| track.duration_ms).sum()
+    }
+}
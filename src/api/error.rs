@@ -0,0 +1,59 @@
+use reqwest::StatusCode;
+use std::{error::Error, fmt};
+
+/// API が失敗のステータスを返したときのエラー（表示はメッセージのまま、分類用にステータスを持つ）
+#[derive(Debug)]
+pub struct ApiError {
+    pub status: StatusCode,
+    message: String,
+}
+
+impl ApiError {
+    pub fn new(status: StatusCode, message: String) -> Self {
+        Self { status, message }
+    }
+}
+
+impl fmt::Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl Error for ApiError {}
+
+/// 画面での出し分け用のエラーの種類
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorKind {
+    /// ネットワークにつながらない（接続できない・タイムアウト）
+    Offline,
+    /// トークンが無効（401）
+    Auth,
+    /// 429 が続いた
+    RateLimited,
+    /// それ以外の API のエラー
+    Api,
+}
+
+impl ErrorKind {
+    /// SpotifyClient のメソッドが返したエラーを分類する（原因をたどって reqwest のエラーも見る）
+    pub fn classify(error: &(dyn Error + 'static)) -> Self {
+        let mut source = Some(error);
+        while let Some(error) = source {
+            if let Some(error) = error.downcast_ref::<ApiError>() {
+                return match error.status {
+                    StatusCode::UNAUTHORIZED => Self::Auth,
+                    StatusCode::TOO_MANY_REQUESTS => Self::RateLimited,
+                    _ => Self::Api,
+                };
+            }
+            if let Some(error) = error.downcast_ref::<reqwest::Error>()
+                && (error.is_connect() || error.is_timeout())
+            {
+                return Self::Offline;
+            }
+            source = error.source();
+        }
+        Self::Api
+    }
+}
@@ -0,0 +1,35 @@
+use crate::config::Config;
+use reqwest::{Client, NoProxy, Proxy};
+use std::time::Duration;
+
+/// デフォルトの User-Agent（`rs-pod/<version>`）
+pub const DEFAULT_USER_AGENT: &str = concat!("rs-pod/", env!("CARGO_PKG_VERSION"));
+/// 使っていない接続を残しておく時間（ポーリングの間隔より十分長く）
+const POOL_IDLE_TIMEOUT: Duration = Duration::from_secs(90);
+/// proxy_url を指定していて NO_PROXY が無いときに直接つなぐ宛先
+const DEFAULT_NO_PROXY: &str = "localhost,127.0.0.1,::1";
+
+/// API と OAuth で共有する HTTP クライアントを作る
+///
+/// 接続を使い回すので、毎回作らずにこのクライアントを clone して使う。
+pub fn build_client(config: &Config) -> reqwest::Result<Client> {
+    let user_agent = config.user_agent.as_deref().unwrap_or(DEFAULT_USER_AGENT);
+    let mut builder = Client::builder()
+        .user_agent(user_agent)
+        .pool_idle_timeout(POOL_IDLE_TIMEOUT);
+    // 応答の無い接続で固まらないように（0 なら待ち続ける）
+    if let Some(timeout) = config.connect_timeout() {
+        builder = builder.connect_timeout(timeout);
+    }
+    if let Some(timeout) = config.read_timeout() {
+        builder = builder.read_timeout(timeout);
+    }
+    // proxy_url が無ければ reqwest が HTTPS_PROXY / HTTP_PROXY / ALL_PROXY / NO_PROXY を読む。
+    // 設定した場合は環境変数のプロキシより優先し、NO_PROXY（無ければローカルホスト）は直接つなぐ。
+    // OAuth のリダイレクトを受ける tiny_http はブラウザからの接続を待つだけなので、プロキシの影響を受けない。
+    if let Some(proxy_url) = config.proxy_url.as_deref().filter(|url| !url.trim().is_empty()) {
+        let no_proxy = NoProxy::from_env().or_else(|| NoProxy::from_string(DEFAULT_NO_PROXY));
+        builder = builder.proxy(Proxy::all(proxy_url)?.no_proxy(no_proxy));
+    }
+    builder.build()
+}
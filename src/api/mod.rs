@@ -1,2 +1,4 @@
+pub mod error;
+pub mod http;
 pub mod oauth;
 pub mod spotify;
\ No newline at end of file
@@ -1,18 +1,69 @@
+use crate::config::{config_dir, Config};
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
-use std::{env, fs, path::Path};
+use std::{
+    env,
+    fs::{self, File, OpenOptions},
+    io,
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
 use rand::{distributions::Alphanumeric, Rng};
 use tiny_http::{Server, Response};
 use url::Url;
 
 const TOKEN_FILE: &str = "spotify_token.json";
+/// 複数のインスタンスが同時にトークンを更新しないためのロックファイル
+const TOKEN_LOCK_FILE: &str = "spotify_token.lock";
+/// rs-pod の機能に必要なスコープ
+const REQUIRED_SCOPES: &[&str] = &[
+    "user-read-playback-state",
+    "user-modify-playback-state",
+    // プロフィールの product（Premium かどうか）
+    "user-read-private",
+    // 自分の（非公開・共同編集を含む）プレイリストへの曲の追加
+    "playlist-read-private",
+    "playlist-read-collaborative",
+    "playlist-modify-public",
+    "playlist-modify-private",
+    // アルバムの保存
+    "user-library-read",
+    "user-library-modify",
+];
+/// Spotify が受け付けるスコープ（これ以外を要求すると認可画面がエラーになる）
+const KNOWN_SCOPES: &[&str] = &[
+    "ugc-image-upload",
+    "user-read-playback-state",
+    "user-modify-playback-state",
+    "user-read-currently-playing",
+    "app-remote-control",
+    "streaming",
+    "playlist-read-private",
+    "playlist-read-collaborative",
+    "playlist-modify-private",
+    "playlist-modify-public",
+    "user-follow-modify",
+    "user-follow-read",
+    "user-read-playback-position",
+    "user-top-read",
+    "user-read-recently-played",
+    "user-library-modify",
+    "user-library-read",
+    "user-read-email",
+    "user-read-private",
+];
+/// ブラウザでの認可を待つ時間の既定値
+pub const DEFAULT_AUTH_TIMEOUT: Duration = Duration::from_secs(120);
 
 #[derive(Debug, Clone,Default)]
 pub struct SpotifyOAuth {
+    client: Client,
     client_id: String,
     client_secret: String,
     redirect_uri: String,
     scopes: Vec<String>,
+    /// リダイレクトが来なければ諦めるまでの時間
+    auth_timeout: Duration,
 }
 
 #[derive(Deserialize, Serialize, Debug)]
@@ -21,27 +72,251 @@ struct TokenResponse {
     token_type: String,
     expires_in: u64,
     refresh_token: Option<String>,
+    /// 許可されたスコープ（スペース区切り）。古いトークンファイルには無い
+    #[serde(default)]
+    scope: Option<String>,
+}
+
+impl TokenResponse {
+    /// 要求するスコープがすべて許可されているか（記録が無ければ分からないので false）
+    fn grants(&self, scopes: &[String]) -> bool {
+        let Some(granted) = &self.scope else {
+            return false;
+        };
+        let granted: Vec<&str> = granted.split_whitespace().collect();
+        scopes.iter().all(|scope| granted.contains(&scope.as_str()))
+    }
+}
+
+/// 他の認証サービスなどで取得済みのトークン（ブラウザでの認可を省略する）
+#[derive(Debug, Clone)]
+pub struct ProvidedToken {
+    pub access_token: String,
+    pub refresh_token: Option<String>,
+}
+
+impl ProvidedToken {
+    /// `--token` で渡されたトークンか環境変数 SPOTIFY_ACCESS_TOKEN（SPOTIFY_REFRESH_TOKEN は任意）
+    pub fn from_arg_or_env(token: Option<String>) -> Option<Self> {
+        dotenv::dotenv().ok();
+
+        let access_token = token
+            .or_else(|| env::var("SPOTIFY_ACCESS_TOKEN").ok())
+            .filter(|token| !token.is_empty())?;
+        let refresh_token = env::var("SPOTIFY_REFRESH_TOKEN")
+            .ok()
+            .filter(|token| !token.is_empty());
+
+        Some(Self {
+            access_token,
+            refresh_token,
+        })
+    }
+
+    /// refresh_token と CLIENT_ID / CLIENT_SECRET があれば更新したアクセストークンを返す
+    pub async fn resolve(self, client: &Client) -> Result<String, Box<dyn std::error::Error>> {
+        let (Some(refresh_token), Ok(client_id), Ok(client_secret)) = (
+            &self.refresh_token,
+            env::var("CLIENT_ID"),
+            env::var("CLIENT_SECRET"),
+        ) else {
+            return Ok(self.access_token);
+        };
+
+        match refresh_access_token(client, &client_id, &client_secret, refresh_token).await? {
+            Some(token) => Ok(token.access_token),
+            None => {
+                tracing::warn!("Refresh token was rejected, using the provided access token as-is");
+                Ok(self.access_token)
+            }
+        }
+    }
+}
+
+/// refresh_token でアクセストークンを更新する（拒否された場合は None）
+async fn refresh_access_token(
+    client: &Client,
+    client_id: &str,
+    client_secret: &str,
+    refresh_token: &str,
+) -> Result<Option<TokenResponse>, Box<dyn std::error::Error>> {
+    tracing::info!("Refreshing access token");
+    let params = [
+        ("grant_type", "refresh_token"),
+        ("refresh_token", refresh_token),
+        ("client_id", client_id),
+        ("client_secret", client_secret),
+    ];
+
+    let res = client
+        .post("https://accounts.spotify.com/api/token")
+        .form(&params)
+        .send()
+        .await?;
+
+    if !res.status().is_success() {
+        return Ok(None);
+    }
+
+    let new_token: TokenResponse = res.json().await?;
+    // refresh_token が返ってこない場合もあるので既存のものを保持
+    Ok(Some(TokenResponse {
+        refresh_token: new_token
+            .refresh_token
+            .or_else(|| Some(refresh_token.to_string())),
+        ..new_token
+    }))
+}
+
+/// 設定で追加されたスコープを足す（Spotify に無い名前は打ち間違いとして警告して外す）
+fn merge_scopes(extra: &[String]) -> Vec<String> {
+    let mut scopes: Vec<String> = REQUIRED_SCOPES.iter().map(|scope| scope.to_string()).collect();
+    for scope in extra {
+        let scope = scope.trim();
+        if !KNOWN_SCOPES.contains(&scope) {
+            tracing::warn!(scope, "Ignoring unknown scope in extra_scopes");
+        } else if !scopes.iter().any(|s| s == scope) {
+            scopes.push(scope.to_string());
+        }
+    }
+    scopes
+}
+
+/// 認証情報が無いときの案内（初回起動でつまずきやすいので設定方法まで書く）
+const CREDENTIALS_HINT: &str = "create a .env with CLIENT_ID, CLIENT_SECRET and REDIRECT_URI \
+from your Spotify app (https://developer.spotify.com/dashboard)";
+
+/// 認証情報（CLIENT_ID, CLIENT_SECRET, REDIRECT_URI）を var で読む
+fn credentials(
+    var: impl Fn(&str) -> Result<String, env::VarError>,
+) -> Result<(String, String, String), Box<dyn std::error::Error>> {
+    let required = |name: &str| required_var(name, var(name));
+    Ok((required("CLIENT_ID")?, required("CLIENT_SECRET")?, required("REDIRECT_URI")?))
+}
+
+/// 必須の変数の値を確かめる（VarError だとどの変数か分からないので名前を入れる）
+fn required_var(name: &str, value: Result<String, env::VarError>) -> Result<String, Box<dyn std::error::Error>> {
+    match value {
+        Ok(value) if !value.trim().is_empty() => Ok(value),
+        Ok(_) => Err(format!("{} is empty — {}", name, CREDENTIALS_HINT).into()),
+        Err(env::VarError::NotPresent) => Err(format!("{} not set — {}", name, CREDENTIALS_HINT).into()),
+        Err(env::VarError::NotUnicode(_)) => Err(format!("{} is not valid UTF-8", name).into()),
+    }
+}
+
+fn token_path() -> PathBuf {
+    config_dir().join(TOKEN_FILE)
+}
+
+fn lock_token_file() -> io::Result<File> {
+    let dir = config_dir();
+    fs::create_dir_all(&dir)?;
+    lock_file(&dir.join(TOKEN_LOCK_FILE))
+}
+
+/// ロックファイルの排他ロックを取る（ファイルを閉じると解放される）
+fn lock_file(path: &Path) -> io::Result<File> {
+    let file = OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(path)?;
+    file.lock()?;
+    Ok(file)
+}
+
+/// 一時ファイルに書いてから置き換える（途中で落ちても壊れたファイルを残さない）
+fn write_token(path: &Path, token: &TokenResponse) -> Result<(), Box<dyn std::error::Error>> {
+    let tmp = path.with_extension("json.tmp");
+    fs::write(&tmp, serde_json::to_string_pretty(token)?)?;
+    fs::rename(&tmp, path)?;
+    Ok(())
+}
+
+/// REDIRECT_URI へのリダイレクトを待ち、クエリの code を返す（ブロックする）
+fn wait_for_callback(server: &Server, redirect_url: &Url, timeout: Duration) -> io::Result<Option<String>> {
+    // ブラウザ側で失敗すると永遠に待つことになるので期限を設ける
+    let deadline = Instant::now() + timeout;
+    loop {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        let Some(request) = server.recv_timeout(remaining)? else {
+            return Err(io::Error::new(
+                io::ErrorKind::TimedOut,
+                format!("Authorization timed out after {}s; run rs-pod again to retry", timeout.as_secs()),
+            ));
+        };
+        let host = request
+            .headers()
+            .iter()
+            .find(|header| header.field.equiv("Host"))
+            .map(|header| header.value.as_str());
+        if let Some(url) = callback_url(redirect_url, host, request.url()) {
+            let code = url.query_pairs().find(|(k, _)| k == "code").map(|(_, v)| v.to_string());
+            let response = Response::from_string("✅ 認証が完了しました！アプリに戻ってください。");
+            request.respond(response)?;
+            return Ok(code);
+        }
+    }
+}
+
+/// 受けたリクエストが REDIRECT_URI 宛てなら、クエリ付きの完全な URL を返す
+///
+/// リクエストには path と query しか無いので REDIRECT_URI を基準に組み立て、
+/// Host ヘッダーがあればホストとポートも REDIRECT_URI と一致するか見る。
+fn callback_url(redirect_url: &Url, host: Option<&str>, request_url: &str) -> Option<Url> {
+    let url = redirect_url.join(request_url).ok()?;
+    let same_origin = |url: &Url| {
+        url.host_str() == redirect_url.host_str()
+            && url.port_or_known_default() == redirect_url.port_or_known_default()
+    };
+    if !same_origin(&url) || url.path() != redirect_url.path() {
+        return None;
+    }
+    if let Some(host) = host {
+        let host_url = Url::parse(&format!("{}://{}", redirect_url.scheme(), host)).ok()?;
+        if !same_origin(&host_url) {
+            return None;
+        }
+    }
+    Some(url)
 }
 
 impl SpotifyOAuth {
-    pub fn new() -> Result<Self, Box<dyn std::error::Error>> {
+    pub fn new(client: Client) -> Result<Self, Box<dyn std::error::Error>> {
         dotenv::dotenv().ok();
         
-        let client_id = env::var("CLIENT_ID")?;
-        let client_secret = env::var("CLIENT_SECRET")?;
-        let redirect_uri = env::var("REDIRECT_URI")?;
-        let scopes = vec!["user-read-playback-state".to_string()];
+        let (client_id, client_secret, redirect_uri) = credentials(|name| env::var(name))?;
+        let scopes = merge_scopes(&[]);
 
         Ok(Self {
+            client,
             client_id,
             client_secret,
             redirect_uri,
             scopes,
+            auth_timeout: DEFAULT_AUTH_TIMEOUT,
         })
     }
 
-    pub async fn init() -> Result<String, Box<dyn std::error::Error >> {
-        let oauth = Self::new()?;
+    pub fn with_auth_timeout(mut self, auth_timeout: Duration) -> Self {
+        self.auth_timeout = auth_timeout;
+        self
+    }
+
+    /// 必要なスコープに加えて要求するスコープ
+    pub fn with_extra_scopes(mut self, extra: &[String]) -> Self {
+        self.scopes = merge_scopes(extra);
+        self
+    }
+
+    /// 環境変数（.env）の認証情報と必要なスコープでトークンを取得する
+    ///
+    /// 保存済みのトークンがあれば更新して使い、無ければブラウザで認可する。
+    /// CLIENT_ID / CLIENT_SECRET / REDIRECT_URI が無ければその名前を含むエラーを返す。
+    pub async fn init(client: Client, config: &Config) -> Result<String, Box<dyn std::error::Error >> {
+        let oauth = Self::new(client)?
+            .with_auth_timeout(config.auth_timeout())
+            .with_extra_scopes(&config.extra_scopes);
         let token = oauth.get_spotify_access_token().await?;
         Ok(token)
     }
@@ -50,64 +325,86 @@ impl SpotifyOAuth {
     pub async fn get_spotify_access_token(
         &self,
     ) -> Result<String, Box<dyn std::error::Error>> {
-        // すでにトークンファイルが存在する場合
-        if Path::new(TOKEN_FILE).exists() {
-            let json = fs::read_to_string(TOKEN_FILE)?;
+        // 別のインスタンスが更新中なら終わるまで待ち、書き込まれたトークンを使う
+        let _lock = tokio::task::spawn_blocking(lock_token_file).await??;
+        let path = token_path();
+
+        // refresh_token の無いトークンやスコープの足りないトークンが残っていた場合は同意画面からやり直す
+        let mut force_consent = false;
+
+        // すでにトークンファイルが存在する場合（以前のカレントディレクトリのものも読む）
+        let existing = [path.as_path(), Path::new(TOKEN_FILE)]
+            .into_iter()
+            .find(|path| path.exists());
+        if let Some(existing) = existing {
+            let json = fs::read_to_string(existing)?;
             let token_data: TokenResponse = serde_json::from_str(&json)?;
 
-            // refresh_token がある場合は再利用
-            if let Some(refresh_token) = &token_data.refresh_token {
-
-                let client = Client::new();
-                let params = [
-                    ("grant_type", "refresh_token"),
-                    ("refresh_token", refresh_token.as_str()),
-                    ("client_id", self.client_id.as_str()),
-                    ("client_secret", self.client_secret.as_str()),
-                ];
-
-                let res = client
-                    .post("https://accounts.spotify.com/api/token")
-                    .form(&params)
-                    .send()
-                    .await?;
-
-                if res.status().is_success() {
-                    let new_token: TokenResponse = res.json().await?;
-                    // refresh_token が返ってこない場合もあるので既存のものを保持
-                    let merged_token = TokenResponse {
-                        refresh_token: Some(refresh_token.clone()),
-                        ..new_token
-                    };
-
-                    fs::write(TOKEN_FILE, serde_json::to_string_pretty(&merged_token)?)?;
+            // 必要なスコープが増えていたら、後で 403 になる前に同意画面からやり直す
+            if !token_data.grants(&self.scopes) {
+                tracing::warn!("Saved token is missing required scopes, re-authorizing");
+                force_consent = true;
+            } else if let Some(refresh_token) = &token_data.refresh_token {
+                // refresh_token がある場合は再利用
+                let refreshed = refresh_access_token(
+                    &self.client,
+                    &self.client_id,
+                    &self.client_secret,
+                    refresh_token,
+                )
+                .await?;
+
+                if let Some(merged_token) = refreshed {
+                    write_token(&path, &merged_token)?;
                     return Ok(merged_token.access_token);
                 } else {
-                    println!("⚠️ Refresh token invalid, doing full auth again...");
+                    tracing::warn!("Refresh token invalid, doing full auth again");
                 }
+            } else {
+                tracing::warn!(
+                    file = %existing.display(),
+                    "Token file has no refresh_token, so it can't be renewed. Re-authorizing once to obtain one"
+                );
+                force_consent = true;
             }
         }
 
         // ⛳ 初回認証（または refresh_token 失効時）
-        println!("🌐 Performing new authorization...");
+        tracing::info!("Performing new authorization");
         let new_token = Self::authorize_spotify(
+            &self.client,
             &self.client_id,
             &self.client_secret,
             &self.redirect_uri,
             &self.scopes,
+            force_consent,
+            self.auth_timeout,
         )
         .await?;
 
-        fs::write(TOKEN_FILE, serde_json::to_string_pretty(&new_token)?)?;
+        if !new_token.grants(&self.scopes) {
+            tracing::warn!(
+                granted = new_token.scope.as_deref().unwrap_or(""),
+                "Spotify did not grant every requested scope; some features may fail"
+            );
+        }
+        if new_token.refresh_token.is_none() {
+            tracing::warn!("Spotify did not return a refresh_token; you will be asked to authorize again next launch");
+        }
+
+        write_token(&path, &new_token)?;
         Ok(new_token.access_token)
     }
 
     /// 🧭 Spotify OAuth 認証（初回のみ実行）
     async fn authorize_spotify(
+        client: &Client,
         client_id: &str,
         client_secret: &str,
         redirect_uri: &str,
         scopes: &[String],
+        force_consent: bool,
+        timeout: Duration,
     ) -> Result<TokenResponse, Box<dyn std::error::Error>> {
         // 1️⃣ state生成
         let state: String = rand::thread_rng()
@@ -125,34 +422,28 @@ impl SpotifyOAuth {
             .append_pair("scope", &scopes.join(" "))
             .append_pair("redirect_uri", redirect_uri)
             .append_pair("state", &state);
+        if force_consent {
+            // 既存の許可を使い回さず同意し直してもらい、refresh_token を確実に発行させる
+            auth_url.query_pairs_mut().append_pair("show_dialog", "true");
+        }
 
-        println!("🔗 Open this URL in your browser:\n{}", auth_url);
+        tracing::info!(url = %auth_url, "Open this URL in your browser");
         webbrowser::open(auth_url.as_str()).ok();
 
-        // 3️⃣ localhost:8888 で待機して code を受け取る
-        println!("Waiting for Spotify redirect...");
-        let server = Server::http("0.0.0.0:8888").expect("");
-
-        let mut code = None;
-        for request in server.incoming_requests() {
-            if request.url().starts_with("/callback") {
-                let url = format!("http://localhost:8888{}", request.url());
-                let parsed = Url::parse(&url)?;
-                if let Some(query_code) = parsed.query_pairs().find(|(k, _)| k == "code") {
-                    code = Some(query_code.1.to_string());
-                }
-
-                let response = Response::from_string("✅ 認証が完了しました！アプリに戻ってください。");
-                request.respond(response)?;
-                break;
-            }
-        }
+        // 3️⃣ REDIRECT_URI のポートで待機して code を受け取る
+        let redirect_url = Url::parse(redirect_uri)?;
+        let port = redirect_url.port_or_known_default().ok_or("REDIRECT_URI has no port")?;
+        tracing::info!(port, "Waiting for Spotify redirect");
+        let server = Server::http(("0.0.0.0", port))
+            .map_err(|e| format!("Failed to listen on port {}: {}", port, e))?;
 
+        // 待っている間もランタイムのスレッドを塞がないよう別スレッドで受ける
+        let code = tokio::task::spawn_blocking(move || wait_for_callback(&server, &redirect_url, timeout)).await??;
         let code = code.ok_or("authorization code not found")?;
-        println!("Got authorization code: {}", code);
+        // code は秘密なのでログには出さない
+        tracing::debug!("Got authorization code");
 
         // 4️⃣ アクセストークン取得
-        let client = Client::new();
         let params = [
             ("grant_type", "authorization_code"),
             ("code", &code),
@@ -171,3 +462,166 @@ impl SpotifyOAuth {
         Ok(token_json)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{callback_url, credentials, lock_file, write_token, TokenResponse};
+    use std::{
+        collections::HashMap,
+        env, fs, process,
+        sync::atomic::{AtomicBool, Ordering},
+        thread,
+    };
+    use url::Url;
+
+    /// name 以外の認証情報がそろった環境で読んだときのエラー
+    fn error_without(name: &str, value: Option<&str>) -> String {
+        let mut vars = HashMap::from([
+            ("CLIENT_ID", "id"),
+            ("CLIENT_SECRET", "secret"),
+            ("REDIRECT_URI", "http://127.0.0.1:8888/callback"),
+        ]);
+        match value {
+            Some(value) => vars.insert(name, value),
+            None => vars.remove(name),
+        };
+        let var = |name: &str| vars.get(name).map(|value| value.to_string()).ok_or(env::VarError::NotPresent);
+        credentials(var).unwrap_err().to_string()
+    }
+
+    #[test]
+    fn credentials_are_read_in_order() {
+        let var = |name: &str| Ok(name.to_lowercase());
+        let (client_id, client_secret, redirect_uri) = credentials(var).unwrap();
+        assert_eq!(
+            (client_id.as_str(), client_secret.as_str(), redirect_uri.as_str()),
+            ("client_id", "client_secret", "redirect_uri")
+        );
+    }
+
+    #[test]
+    fn missing_client_id_is_named() {
+        let error = error_without("CLIENT_ID", None);
+        assert!(error.starts_with("CLIENT_ID not set"), "{}", error);
+    }
+
+    #[test]
+    fn missing_client_secret_is_named() {
+        let error = error_without("CLIENT_SECRET", None);
+        assert!(error.starts_with("CLIENT_SECRET not set"), "{}", error);
+    }
+
+    #[test]
+    fn missing_redirect_uri_is_named() {
+        let error = error_without("REDIRECT_URI", None);
+        assert!(error.starts_with("REDIRECT_URI not set"), "{}", error);
+    }
+
+    #[test]
+    fn blank_credential_is_named() {
+        let error = error_without("CLIENT_SECRET", Some("  "));
+        assert!(error.starts_with("CLIENT_SECRET is empty"), "{}", error);
+    }
+
+    fn code(redirect_uri: &str, host: Option<&str>, request_url: &str) -> Option<String> {
+        let redirect_url = Url::parse(redirect_uri).unwrap();
+        let url = callback_url(&redirect_url, host, request_url)?;
+        url.query_pairs().find(|(k, _)| k == "code").map(|(_, v)| v.to_string())
+    }
+
+    #[test]
+    fn callback_on_loopback_ip_and_custom_port() {
+        assert_eq!(
+            code("http://127.0.0.1:9000/callback", Some("127.0.0.1:9000"), "/callback?code=abc&state=xyz"),
+            Some("abc".to_string())
+        );
+    }
+
+    #[test]
+    fn callback_without_host_header_uses_redirect_uri() {
+        assert_eq!(
+            code("http://localhost:8888/callback", None, "/callback?code=abc"),
+            Some("abc".to_string())
+        );
+    }
+
+    #[test]
+    fn callback_on_another_path_is_ignored() {
+        // favicon などブラウザが勝手に取りに来るもの
+        assert_eq!(code("http://127.0.0.1:9000/callback", Some("127.0.0.1:9000"), "/favicon.ico"), None);
+        assert_eq!(code("http://127.0.0.1:9000/callback", Some("127.0.0.1:9000"), "/?code=abc"), None);
+    }
+
+    #[test]
+    fn callback_for_another_host_or_port_is_ignored() {
+        let redirect_uri = "http://127.0.0.1:9000/callback";
+        assert_eq!(code(redirect_uri, Some("localhost:9000"), "/callback?code=abc"), None);
+        assert_eq!(code(redirect_uri, Some("127.0.0.1:8888"), "/callback?code=abc"), None);
+        assert_eq!(code(redirect_uri, Some("127.0.0.1"), "/callback?code=abc"), None);
+        // スキーム相対の URL で別のホストを指されても受け付けない
+        assert_eq!(code(redirect_uri, None, "//example.com/callback?code=abc"), None);
+    }
+
+    #[test]
+    fn default_port_matches_host_without_port() {
+        assert_eq!(
+            code("http://localhost/callback", Some("localhost"), "/callback?code=abc"),
+            Some("abc".to_string())
+        );
+        assert_eq!(
+            code("http://localhost/callback", Some("localhost:80"), "/callback?code=abc"),
+            Some("abc".to_string())
+        );
+    }
+
+    #[test]
+    fn concurrent_refreshes_leave_a_valid_token_file() {
+        let dir = env::temp_dir().join(format!("rs-pod-token-{}", process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let token_path = dir.join("spotify_token.json");
+        let lock_path = dir.join("spotify_token.lock");
+        let refreshing = AtomicBool::new(false);
+        let done = AtomicBool::new(false);
+
+        thread::scope(|scope| {
+            // ロックを取らずに読む側からも、いつ読んでも壊れていない JSON に見える
+            scope.spawn(|| {
+                while !done.load(Ordering::SeqCst) {
+                    if let Ok(json) = fs::read_to_string(&token_path) {
+                        serde_json::from_str::<TokenResponse>(&json).expect("token file was torn");
+                    }
+                }
+            });
+            let refreshes: Vec<_> = (0..8)
+                .map(|i| {
+                    let (token_path, lock_path, refreshing) = (&token_path, &lock_path, &refreshing);
+                    scope.spawn(move || {
+                        for round in 0..20 {
+                            let _lock = lock_file(lock_path).unwrap();
+                            assert!(!refreshing.swap(true, Ordering::SeqCst), "two refreshes held the lock");
+                            let token = TokenResponse {
+                                access_token: format!("access-{}-{}", i, round),
+                                token_type: "Bearer".to_string(),
+                                expires_in: 3600,
+                                refresh_token: Some("refresh".to_string()),
+                                scope: None,
+                            };
+                            write_token(token_path, &token).unwrap();
+                            refreshing.store(false, Ordering::SeqCst);
+                        }
+                    })
+                })
+                .collect();
+            for refresh in refreshes {
+                refresh.join().unwrap();
+            }
+            done.store(true, Ordering::SeqCst);
+        });
+
+        let token: TokenResponse = serde_json::from_str(&fs::read_to_string(&token_path).unwrap()).unwrap();
+        assert!(token.access_token.starts_with("access-"));
+        assert_eq!(token.refresh_token.as_deref(), Some("refresh"));
+        let _ = fs::remove_dir_all(dir);
+    }
+}
@@ -1,39 +1,199 @@
-use reqwest::Client;
+use crate::api::{error::ApiError, http, oauth::{ProvidedToken, SpotifyOAuth}};
+use crate::config::Config;
+use crate::image_cache::ImageCache;
+use reqwest::{header::RETRY_AFTER, Client, RequestBuilder, Response, StatusCode};
 use serde::Deserialize;
 use image::{DynamicImage, ImageReader};
-use std::io::Cursor;
+use std::{
+    io::Cursor,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+use tokio::{sync::Semaphore, task::JoinSet};
+
+/// 冪等な GET の最大リトライ回数
+const MAX_RETRIES: u32 = 3;
+const INITIAL_BACKOFF: Duration = Duration::from_millis(250);
+/// 429 で Retry-After が無い、または長すぎる場合の待ち時間
+const DEFAULT_RETRY_AFTER: Duration = Duration::from_secs(1);
+const MAX_RETRY_AFTER: Duration = Duration::from_secs(30);
+/// Web API のベース URL（テストではモックサーバーに差し替える）
+pub const DEFAULT_BASE_URL: &str = "https://api.spotify.com";
 
 pub enum SkipDirection {
     Next,
     Previous,
 }
 
+/// クローンは HTTP クライアントとレート制限の記録を共有する（バックグラウンド取得用）
+#[derive(Clone)]
 pub struct SpotifyClient {
     client: Client,
     access_token: String,
+    base_url: String,
     pub spotify_player : SpotifyPlayer,
+    /// ログイン中のユーザー（init で一度だけ取得する）
+    pub current_user: Option<CurrentUser>,
+    /// 429 の Retry-After で指定された再開時刻（UI はそれまでポーリングしない）
+    rate_limited_until: Arc<Mutex<Option<Instant>>>,
+    image_cache: ImageCache,
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Debug, Clone, Default)]
 pub struct SpotifyPlayer {
     pub is_playing: bool,
     pub item: Option<Track>,
     pub progress_ms: Option<i64>,
+    #[serde(default)]
+    pub currently_playing_type: PlayingType,
+    /// 再生中のデバイス（何も再生していないときは null）
+    pub device: Option<Device>,
+    /// 再生元のプレイリストやアルバム（キューから直接再生したときなどは null）
+    pub context: Option<PlaybackContext>,
+    #[serde(default)]
+    pub shuffle_state: bool,
+    #[serde(default)]
+    pub repeat_state: RepeatState,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct PlaybackContext {
+    pub uri: String,
+    /// "playlist" / "album" / "artist" / "show" など
+    #[serde(rename = "type", default)]
+    pub kind: Option<String>,
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum RepeatState {
+    #[default]
+    Off,
+    /// プレイリストやアルバム全体をリピート
+    Context,
+    /// 1曲リピート
+    Track,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct Device {
+    /// 制限付きデバイスでは null
+    pub id: Option<String>,
+    pub name: String,
+    #[serde(default)]
+    pub is_active: bool,
+    pub volume_percent: Option<u8>,
+    #[serde(default)]
+    pub supports_volume: bool,
 }
 
 #[derive(Deserialize, Debug)]
+struct DevicesResponse {
+    devices: Vec<Device>,
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum PlayingType {
+    Track,
+    Episode,
+    Ad,
+    #[default]
+    #[serde(other)]
+    Unknown,
+}
+
+/// 曲またはポッドキャストのエピソード
+#[derive(Deserialize, Debug, Clone)]
 pub struct Track {
+    /// ローカルファイルの場合は null
+    pub id: Option<String>,
+    #[serde(default)]
+    pub uri: String,
     pub name: String,
+    #[serde(default)]
     pub artists: Vec<Artist>,
     pub duration_ms: i64,
+    #[serde(default)]
+    pub explicit: bool,
+    #[serde(default)]
     pub album: Album,
+    /// 0〜100（エピソードには無い）
+    pub popularity: Option<u32>,
+    pub track_number: Option<u32>,
+    /// エピソードの場合のみ
+    pub show: Option<Show>,
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Debug, Clone, Default)]
 pub struct Album {
+    /// ローカルファイルの場合は null
+    #[serde(default)]
+    pub id: Option<String>,
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub artists: Vec<Artist>,
+    /// "2024", "2024-05", "2024-05-17" のいずれか
+    pub release_date: Option<String>,
+    pub total_tracks: Option<u32>,
     pub images: Vec<Image>,
 }
 
+impl Album {
+    pub fn artist_names(&self) -> String {
+        self.artists.iter().map(|a| a.name.as_str()).collect::<Vec<_>>().join(", ")
+    }
+
+    /// 発売日の年
+    pub fn release_year(&self) -> Option<&str> {
+        self.release_date.as_deref()?.get(..4)
+    }
+}
+
+impl SpotifyPlayer {
+    /// `rs-pod now --json` やソケット配信で使う再生状態の JSON
+    pub fn state_json(&self) -> serde_json::Value {
+        match &self.item {
+            Some(track) => serde_json::json!({
+                "is_playing": self.is_playing,
+                "name": track.name,
+                "artists": track.artist_list(),
+                "uri": track.uri,
+                "progress_ms": self.progress_ms.unwrap_or(0),
+                "duration_ms": track.duration_ms,
+            }),
+            None => serde_json::json!({ "is_playing": false }),
+        }
+    }
+}
+
+impl Track {
+    /// 表示用のアーティスト名の一覧（エピソードは番組名）
+    pub fn artist_list(&self) -> Vec<&str> {
+        match &self.show {
+            Some(show) => vec![show.name.as_str()],
+            None => self.artists.iter().map(|a| a.name.as_str()).collect(),
+        }
+    }
+
+    pub fn artist_names(&self) -> String {
+        self.artist_list().join(", ")
+    }
+
+    /// open.spotify.com の共有リンク（ローカルファイルは id が無いので None）
+    pub fn share_url(&self) -> Option<String> {
+        let id = self.id.as_deref()?;
+        let kind = if self.show.is_some() { "episode" } else { "track" };
+        Some(format!("https://open.spotify.com/{}/{}", kind, id))
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct Show {
+    pub name: String,
+}
+
 #[derive(Deserialize, Debug, Clone)]
 pub struct Image {
     pub url: String,
@@ -41,11 +201,20 @@ pub struct Image {
     pub width: Option<i32>,
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Debug, Clone)]
 pub struct Artist {
     pub name: String,
 }
 
+/// コンテキストのどこから再生するか
+#[derive(Debug, Clone, Copy)]
+pub enum PlayOffset<'a> {
+    /// 0 から数えた位置
+    Position(usize),
+    /// コンテキスト内の曲の URI
+    Uri(&'a str),
+}
+
 #[derive(Deserialize, Debug)]
 pub struct PlaylistsResponse {
     pub items: Vec<Playlist>,
@@ -57,6 +226,71 @@ pub struct Playlist {
     pub name: String,
     pub tracks: PlaylistTracks,
     pub images: Vec<Image>,
+    pub owner: Option<Owner>,
+    /// HTML エスケープされている（空文字のこともある）
+    #[serde(default)]
+    pub description: Option<String>,
+    /// 共同編集（持ち主以外も曲を追加できる）
+    #[serde(default)]
+    pub collaborative: bool,
+    /// 変更のたびに変わる版（曲の並べ替えや削除で他の人の変更を上書きしないために渡す）
+    #[serde(default)]
+    pub snapshot_id: Option<String>,
+    /// 一覧の取得では返ってこない（get_playlist のときだけ）
+    #[serde(default)]
+    pub followers: Option<Followers>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct Followers {
+    pub total: u64,
+}
+
+impl Playlist {
+    /// このユーザーが曲を追加できるか
+    pub fn is_editable_by(&self, user_id: &str) -> bool {
+        self.collaborative || self.owner.as_ref().is_some_and(|owner| owner.id == user_id)
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct Owner {
+    #[serde(default)]
+    pub id: String,
+    pub display_name: Option<String>,
+}
+
+/// GET /v1/me で取れるログイン中のユーザー
+#[derive(Deserialize, Debug, Clone)]
+pub struct CurrentUser {
+    pub id: String,
+    pub display_name: Option<String>,
+    /// "premium" / "free" など（user-read-private スコープが無いと返ってこない）
+    #[serde(default)]
+    pub product: Option<String>,
+}
+
+impl CurrentUser {
+    /// Premium 限定の操作ができるか（不明な場合は使える前提にして API の判断に任せる）
+    pub fn is_premium(&self) -> bool {
+        self.product.as_deref().is_none_or(|product| product == "premium")
+    }
+
+    pub fn name(&self) -> &str {
+        self.display_name.as_deref().unwrap_or(&self.id)
+    }
+}
+
+/// プレイリストを変更したときのレスポンス
+#[derive(Deserialize, Debug)]
+struct SnapshotResponse {
+    snapshot_id: String,
+}
+
+/// 名前だけ読むレスポンス（再生元の名前の取得用）
+#[derive(Deserialize, Debug)]
+struct NameResponse {
+    name: String,
 }
 
 #[derive(Deserialize, Debug, Clone)]
@@ -64,37 +298,173 @@ pub struct PlaylistTracks {
     pub total: i32,
 }
 
-impl Default for SpotifyPlayer {
-    fn default() -> Self {
-        Self {
-            is_playing: false,
-            item: None,
-            progress_ms: None,
-        }
-    }
+#[derive(Deserialize, Debug)]
+struct PlaylistItemsResponse {
+    items: Vec<PlaylistItem>,
+    next: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+struct PlaylistItem {
+    /// 削除された曲などは null
+    track: Option<Track>,
+}
+
+#[derive(Deserialize, Debug)]
+struct NewReleasesResponse {
+    albums: AlbumsPage,
+}
+
+#[derive(Deserialize, Debug)]
+struct AlbumsPage {
+    items: Vec<Album>,
+    next: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+struct SearchResponse {
+    tracks: SearchTracks,
+}
+
+#[derive(Deserialize, Debug)]
+struct SearchTracks {
+    items: Vec<Track>,
+}
+
+/// アルバムの曲（album を含まない簡易版の Track）
+#[derive(Deserialize, Debug)]
+struct AlbumTracksResponse {
+    items: Vec<Track>,
+    next: Option<String>,
 }
 
 impl SpotifyClient {
     pub fn new(client: Client, access_token: &String) -> Self {
         Self {
             spotify_player: SpotifyPlayer::default(),
+            current_user: None,
             client,
             access_token: access_token.to_string(),
+            base_url: DEFAULT_BASE_URL.to_string(),
+            rate_limited_until: Arc::new(Mutex::new(None)),
+            image_cache: ImageCache::default(),
+        }
+    }
+
+    /// API の向き先を変える（末尾の / は無視する）
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into().trim_end_matches('/').to_string();
+        self
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("{}{}", self.base_url, path)
+    }
+
+    /// 設定とトークンからクライアントを作る（トークンが無ければブラウザで認可する）
+    pub async fn connect(
+        config: &Config,
+        provided_token: Option<ProvidedToken>,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
+        let client = http::build_client(config)?;
+
+        let validate = provided_token.is_some();
+        let access_token = match provided_token {
+            Some(token) => token.resolve(&client).await?,
+            None => SpotifyOAuth::init(client.clone(), config).await?,
+        };
+
+        let spotify_client = Self::new(client, &access_token);
+        if validate {
+            spotify_client.validate_token().await?;
         }
+        Ok(spotify_client)
     }
 
     pub async fn init(mut self) -> Result<Self, Box<dyn std::error::Error>> {
         self.spotify_player = self.get_current_playback().await?;
+        // 取れなくても再生はできるので起動は止めない
+        match self.get_current_user().await {
+            Ok(user) => self.current_user = Some(user),
+            Err(e) => tracing::warn!(error = %e, "Failed to fetch user profile"),
+        }
         Ok(self)
     }
 
+    /// 外部から渡されたトークンが使えるか確認する（/v1/me と再生状態の取得で権限も見る）
+    pub async fn validate_token(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let request = self.client
+            .get(self.url("/v1/me"))
+            .bearer_auth(&self.access_token);
+        let res = self.send_with_retry(request).await?;
+        match res.status() {
+            StatusCode::UNAUTHORIZED => {
+                return Err("Access token is invalid or expired".into());
+            }
+            status if !status.is_success() => {
+                return Err(api_error("Failed to validate access token", res).await);
+            }
+            _ => {}
+        }
+
+        // /v1/me はスコープ無しでも通るので、必要なスコープを使う API も試す
+        let request = self.client
+            .get(self.url("/v1/me/player"))
+            .bearer_auth(&self.access_token);
+        let res = self.send_with_retry(request).await?;
+        if matches!(res.status(), StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN) {
+            return Err(format!(
+                "Access token lacks required scopes (user-read-playback-state, user-modify-playback-state): {}",
+                res.status()
+            )
+            .into());
+        }
+        Ok(())
+    }
+
+    /// 接続エラーと 5xx のときだけ指数バックオフで再試行する（4xx は即座に返す）
+    /// 429 の場合は待たずに返し、Retry-After の再開時刻だけ記録する
+    async fn send_with_retry(&self, request: RequestBuilder) -> Result<Response, reqwest::Error> {
+        let mut backoff = INITIAL_BACKOFF;
+        let mut attempt = 0;
+        loop {
+            let req = request
+                .try_clone()
+                .expect("GET requests have no streaming body");
+            match req.send().await {
+                Ok(res) if res.status() == StatusCode::TOO_MANY_REQUESTS => {
+                    let wait = retry_after(&res);
+                    tracing::warn!(url = %res.url(), ?wait, "Rate limited");
+                    *self.rate_limited_until.lock().unwrap() = Some(Instant::now() + wait);
+                    return Ok(res);
+                }
+                Ok(res) if res.status().is_server_error() && attempt < MAX_RETRIES => {}
+                Err(e) if (e.is_connect() || e.is_timeout()) && attempt < MAX_RETRIES => {}
+                result => return result,
+            }
+
+            attempt += 1;
+            tracing::debug!(attempt, ?backoff, "Retrying request");
+            tokio::time::sleep(backoff).await;
+            backoff *= 2;
+        }
+    }
+
+    /// 429 で指定された再開時刻がまだ来ていなければ返す
+    pub fn rate_limited_until(&self) -> Option<Instant> {
+        let mut until = self.rate_limited_until.lock().unwrap();
+        if until.is_some_and(|until| until <= Instant::now()) {
+            *until = None;
+        }
+        *until
+    }
+
     pub async fn get_current_playback(&self) -> Result<SpotifyPlayer, Box<dyn std::error::Error>> {
-        let res = self.client
-            .get("https://api.spotify.com/v1/me/player")
+        let request = self.client
+            .get(self.url("/v1/me/player"))
             .bearer_auth(&self.access_token)
-            .query(&[("market", "US")])
-            .send()
-            .await?;
+            .query(&[("market", "US"), ("additional_types", "episode")]);
+        let res = self.send_with_retry(request).await?;
 
         // 204 No Content: 何も再生していない場合
         if res.status().as_u16() == 204 {
@@ -102,28 +472,64 @@ impl SpotifyClient {
         }
 
         if !res.status().is_success() {
-            return Err(format!("Failed to fetch player info: {}", res.status()).into());
+            return Err(api_error("Failed to fetch player info", res).await);
         }
 
         let player: SpotifyPlayer = res.json().await?;
         Ok(player)
     }
 
+    /// 再生に使えるデバイスの一覧（Spotify を開いているものだけ）
+    pub async fn get_devices(&self) -> Result<Vec<Device>, Box<dyn std::error::Error>> {
+        let request = self.client
+            .get(self.url("/v1/me/player/devices"))
+            .bearer_auth(&self.access_token);
+        let res = self.send_with_retry(request).await?;
+
+        if !res.status().is_success() {
+            return Err(api_error("Failed to fetch devices", res).await);
+        }
+
+        let response: DevicesResponse = res.json().await?;
+        Ok(response.devices)
+    }
+
+    /// 指定したデバイスに再生を移す（play が true なら再生を始める）
+    pub async fn transfer_playback(&self, device_id: &str, play: bool) -> Result<(), Box<dyn std::error::Error>> {
+        let body = serde_json::json!({
+            "device_ids": [device_id],
+            "play": play
+        });
+
+        let res = self.client
+            .put(self.url("/v1/me/player"))
+            .bearer_auth(&self.access_token)
+            .json(&body)
+            .send()
+            .await?;
+
+        if !res.status().is_success() {
+            return Err(api_error("Failed to transfer playback", res).await);
+        }
+
+        Ok(())
+    }
+
     pub async fn skip_track(&mut self, direction: SkipDirection) -> Result<(), Box<dyn std::error::Error>> {
         let endpoint = match direction {
-            SkipDirection::Next => "https://api.spotify.com/v1/me/player/next",
-            SkipDirection::Previous => "https://api.spotify.com/v1/me/player/previous",
+            SkipDirection::Next => "/v1/me/player/next",
+            SkipDirection::Previous => "/v1/me/player/previous",
         };
 
         let res = self.client
-            .post(endpoint)
+            .post(self.url(endpoint))
             .bearer_auth(&self.access_token)
             .header("Content-Length", "0")
             .send()
             .await?;
 
         if !res.status().is_success() {
-            return Err(format!("Failed to skip track: {}", res.status()).into());
+            return Err(api_error("Failed to skip track", res).await);
         }
 
         // Update current playback info
@@ -131,9 +537,103 @@ impl SpotifyClient {
         Ok(())
     }
 
+    pub async fn pause(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let res = self.client
+            .put(self.url("/v1/me/player/pause"))
+            .bearer_auth(&self.access_token)
+            .header("Content-Length", "0")
+            .send()
+            .await?;
+
+        if !res.status().is_success() {
+            return Err(api_error("Failed to pause playback", res).await);
+        }
+        Ok(())
+    }
+
+    /// 一時停止中の再生を再開する
+    pub async fn resume(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let res = self.client
+            .put(self.url("/v1/me/player/play"))
+            .bearer_auth(&self.access_token)
+            .header("Content-Length", "0")
+            .send()
+            .await?;
+
+        if !res.status().is_success() {
+            return Err(api_error("Failed to resume playback", res).await);
+        }
+        Ok(())
+    }
+
+    /// 再生中なら一時停止、止まっていれば再開する（次の取得を待たずに表示も切り替える）
+    pub async fn toggle_playback(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        if self.spotify_player.is_playing {
+            self.pause().await?;
+        } else {
+            self.resume().await?;
+        }
+        self.spotify_player.is_playing = !self.spotify_player.is_playing;
+        Ok(())
+    }
+
+    /// 音量を 0〜100 で設定する（次の取得を待たずに表示も更新する）
+    pub async fn set_volume(&mut self, volume_percent: u8) -> Result<(), Box<dyn std::error::Error>> {
+        let volume_percent = volume_percent.min(100);
+        let res = self.client
+            .put(self.url("/v1/me/player/volume"))
+            .bearer_auth(&self.access_token)
+            .query(&[("volume_percent", volume_percent)])
+            .header("Content-Length", "0")
+            .send()
+            .await?;
+
+        if !res.status().is_success() {
+            return Err(api_error("Failed to set volume", res).await);
+        }
+
+        if let Some(device) = self.spotify_player.device.as_mut() {
+            device.volume_percent = Some(volume_percent);
+        }
+        Ok(())
+    }
+
+    pub async fn seek(&self, position_ms: i64) -> Result<(), Box<dyn std::error::Error>> {
+        let res = self.client
+            .put(self.url("/v1/me/player/seek"))
+            .bearer_auth(&self.access_token)
+            .query(&[("position_ms", position_ms)])
+            .header("Content-Length", "0")
+            .send()
+            .await?;
+
+        if !res.status().is_success() {
+            return Err(api_error("Failed to seek", res).await);
+        }
+
+        Ok(())
+    }
+
+    /// シャッフル再生を切り替える（再生中のデバイスが無いと 404）
+    pub async fn set_shuffle(&mut self, state: bool) -> Result<(), Box<dyn std::error::Error>> {
+        let res = self.client
+            .put(self.url("/v1/me/player/shuffle"))
+            .bearer_auth(&self.access_token)
+            .query(&[("state", state)])
+            .header("Content-Length", "0")
+            .send()
+            .await?;
+
+        if !res.status().is_success() {
+            return Err(api_error("Failed to set shuffle", res).await);
+        }
+
+        self.spotify_player.shuffle_state = state;
+        Ok(())
+    }
+
     pub async fn download_image(&self, url: &str) -> Result<DynamicImage, Box<dyn std::error::Error>> {
-        // URLから画像を取得
-        let bytes = self.client.get(url).send().await?.bytes().await?;
+        let bytes = self.fetch_image_bytes(url).await?;
 
         // image crate でデコード
         let dyn_img = ImageReader::new(Cursor::new(bytes))
@@ -143,42 +643,545 @@ impl SpotifyClient {
         Ok(dyn_img)
     }
 
+    /// 画像をまとめてディスクキャッシュに入れておく（同時に max_concurrent 件まで、失敗はログに出すだけ）
+    ///
+    /// この Future を破棄すると取得中のものも止まる。
+    pub async fn prefetch_images(&self, urls: Vec<String>, max_concurrent: usize) {
+        let semaphore = Arc::new(Semaphore::new(max_concurrent.max(1)));
+        // JoinSet は破棄されると中のタスクを abort する
+        let mut tasks = JoinSet::new();
+        for url in urls {
+            let client = self.clone();
+            let semaphore = Arc::clone(&semaphore);
+            tasks.spawn(async move {
+                let Ok(_permit) = semaphore.acquire_owned().await else {
+                    return;
+                };
+                if let Err(e) = client.fetch_image_bytes(&url).await {
+                    tracing::debug!(error = %e, url, "Failed to prefetch image");
+                }
+            });
+        }
+        while tasks.join_next().await.is_some() {}
+    }
+
+    /// キャッシュになければ URL から画像を取得してキャッシュする
+    async fn fetch_image_bytes(&self, url: &str) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+        if let Some(bytes) = self.image_cache.get(url) {
+            return Ok(bytes);
+        }
+        let bytes = self.client.get(url).send().await?.error_for_status()?.bytes().await?.to_vec();
+        // キャッシュへの書き込み失敗は表示に影響しないので無視
+        let _ = self.image_cache.put(url, &bytes);
+        Ok(bytes)
+    }
+
     pub async fn get_user_playlists(&self) -> Result<Vec<Playlist>, Box<dyn std::error::Error>> {
+        let request = self.client
+            .get(self.url("/v1/me/playlists"))
+            .bearer_auth(&self.access_token)
+            .query(&[("limit", "50"), ("market", "US")]);
+        let res = self.send_with_retry(request).await?;
+
+        if !res.status().is_success() {
+            return Err(api_error("Failed to fetch playlists", res).await);
+        }
+
+        let playlists: PlaylistsResponse = res.json().await?;
+        Ok(playlists.items)
+    }
+
+    /// プレイリストの曲をすべて取得（ページングをたどる）
+    pub async fn get_playlist_tracks(&self, playlist_id: &str) -> Result<Vec<Track>, Box<dyn std::error::Error>> {
+        let mut tracks = Vec::new();
+        let mut request = self.client
+            .get(self.url(&format!("/v1/playlists/{}/tracks", playlist_id)))
+            .query(&[("limit", "100"), ("market", "US"), ("additional_types", "episode")]);
+
+        loop {
+            let res = self.send_with_retry(request.bearer_auth(&self.access_token)).await?;
+            if !res.status().is_success() {
+                return Err(api_error("Failed to fetch playlist tracks", res).await);
+            }
+
+            let page: PlaylistItemsResponse = res.json().await?;
+            tracks.extend(page.items.into_iter().filter_map(|item| item.track));
+            match page.next {
+                Some(next) => request = self.client.get(next),
+                None => break,
+            }
+        }
+
+        Ok(tracks)
+    }
+
+    /// 新着アルバムをすべて取得（ページングをたどる）
+    pub async fn get_new_releases(&self) -> Result<Vec<Album>, Box<dyn std::error::Error>> {
+        let mut albums = Vec::new();
+        let mut request = self.client
+            .get(self.url("/v1/browse/new-releases"))
+            .query(&[("limit", "50")]);
+
+        loop {
+            let res = self.send_with_retry(request.bearer_auth(&self.access_token)).await?;
+            if !res.status().is_success() {
+                return Err(api_error("Failed to fetch new releases", res).await);
+            }
+
+            let page: NewReleasesResponse = res.json().await?;
+            albums.extend(page.albums.items);
+            match page.albums.next {
+                Some(next) => request = self.client.get(next),
+                None => break,
+            }
+        }
+
+        Ok(albums)
+    }
+
+    /// アルバムの曲をすべて取得（ページングをたどる）
+    pub async fn get_album_tracks(&self, album_id: &str) -> Result<Vec<Track>, Box<dyn std::error::Error>> {
+        let mut tracks = Vec::new();
+        let mut request = self.client
+            .get(self.url(&format!("/v1/albums/{}/tracks", album_id)))
+            .query(&[("limit", "50"), ("market", "US")]);
+
+        loop {
+            let res = self.send_with_retry(request.bearer_auth(&self.access_token)).await?;
+            if !res.status().is_success() {
+                return Err(api_error("Failed to fetch album tracks", res).await);
+            }
+
+            let page: AlbumTracksResponse = res.json().await?;
+            tracks.extend(page.items);
+            match page.next {
+                Some(next) => request = self.client.get(next),
+                None => break,
+            }
+        }
+
+        Ok(tracks)
+    }
+
+    /// 曲を検索する（上位 20 件）
+    pub async fn search_tracks(&self, query: &str) -> Result<Vec<Track>, Box<dyn std::error::Error>> {
+        let request = self.client
+            .get(self.url("/v1/search"))
+            .bearer_auth(&self.access_token)
+            .query(&[("q", query), ("type", "track"), ("limit", "20"), ("market", "US")]);
+        let res = self.send_with_retry(request).await?;
+
+        if !res.status().is_success() {
+            return Err(api_error("Failed to search", res).await);
+        }
+
+        let response: SearchResponse = res.json().await?;
+        Ok(response.tracks.items)
+    }
+
+    pub async fn add_to_queue(&self, uri: &str) -> Result<(), Box<dyn std::error::Error>> {
         let res = self.client
-            .get("https://api.spotify.com/v1/me/playlists")
+            .post(self.url("/v1/me/player/queue"))
             .bearer_auth(&self.access_token)
-            .query(&[("limit", "50"), ("market", "US")])
+            .query(&[("uri", uri)])
+            .header("Content-Length", "0")
             .send()
             .await?;
 
         if !res.status().is_success() {
-            return Err(format!("Failed to fetch playlists: {}", res.status()).into());
+            return Err(api_error("Failed to add to queue", res).await);
         }
 
-        let playlists: PlaylistsResponse = res.json().await?;
-        Ok(playlists.items)
+        Ok(())
     }
 
-    pub async fn play_playlist(&self, playlist_id: &str) -> Result<(), Box<dyn std::error::Error>> {
-        let body = serde_json::json!({
-            "context_uri": format!("spotify:playlist:{}", playlist_id),
-            "offset": {
-                "position": 0
-            },
-            "position_ms": 0
+    pub async fn get_current_user(&self) -> Result<CurrentUser, Box<dyn std::error::Error>> {
+        let request = self.client
+            .get(self.url("/v1/me"))
+            .bearer_auth(&self.access_token);
+        let res = self.send_with_retry(request).await?;
+
+        if !res.status().is_success() {
+            return Err(api_error("Failed to fetch user profile", res).await);
+        }
+
+        let user: CurrentUser = res.json().await?;
+        Ok(user)
+    }
+
+    /// プレイリストの末尾に曲を追加する
+    pub async fn add_track_to_playlist(&self, playlist_id: &str, uri: &str) -> Result<(), Box<dyn std::error::Error>> {
+        let body = serde_json::json!({ "uris": [uri] });
+
+        let res = self.client
+            .post(self.url(&format!("/v1/playlists/{}/tracks", playlist_id)))
+            .bearer_auth(&self.access_token)
+            .json(&body)
+            .send()
+            .await?;
+
+        if !res.status().is_success() {
+            return Err(api_error("Failed to add to playlist", res).await);
+        }
+
+        Ok(())
+    }
+
+    /// プレイリストを作る（同じ名前があっても別のプレイリストとして作られる）
+    pub async fn create_playlist(&self, user_id: &str, name: &str, public: bool) -> Result<Playlist, Box<dyn std::error::Error>> {
+        let body = serde_json::json!({ "name": name, "public": public });
+
+        let res = self.client
+            .post(self.url(&format!("/v1/users/{}/playlists", user_id)))
+            .bearer_auth(&self.access_token)
+            .json(&body)
+            .send()
+            .await?;
+
+        if !res.status().is_success() {
+            return Err(api_error("Failed to create playlist", res).await);
+        }
+
+        Ok(res.json().await?)
+    }
+
+    /// プレイリストから曲を削除する（同じ曲が何度も入っていればすべて）。変更後の snapshot_id を返す
+    ///
+    /// snapshot_id を渡すと、その版の後に他の人が加えた変更を壊さないように削除される。
+    pub async fn remove_track_from_playlist(
+        &self,
+        playlist_id: &str,
+        uri: &str,
+        snapshot_id: Option<&str>,
+    ) -> Result<String, Box<dyn std::error::Error>> {
+        let mut body = serde_json::json!({ "tracks": [{ "uri": uri }] });
+        if let Some(snapshot_id) = snapshot_id {
+            body["snapshot_id"] = snapshot_id.into();
+        }
+
+        let res = self.client
+            .delete(self.url(&format!("/v1/playlists/{}/tracks", playlist_id)))
+            .bearer_auth(&self.access_token)
+            .json(&body)
+            .send()
+            .await?;
+
+        if !res.status().is_success() {
+            return Err(api_error("Failed to remove from playlist", res).await);
+        }
+
+        let snapshot: SnapshotResponse = res.json().await?;
+        Ok(snapshot.snapshot_id)
+    }
+
+    /// range_start 番目の曲を insert_before 番目の曲の前へ移す（どちらも 0 から、末尾へは曲数を渡す）。変更後の snapshot_id を返す
+    pub async fn reorder_playlist_track(
+        &self,
+        playlist_id: &str,
+        range_start: usize,
+        insert_before: usize,
+        snapshot_id: Option<&str>,
+    ) -> Result<String, Box<dyn std::error::Error>> {
+        let mut body = serde_json::json!({
+            "range_start": range_start,
+            "insert_before": insert_before,
+            "range_length": 1,
         });
+        if let Some(snapshot_id) = snapshot_id {
+            body["snapshot_id"] = snapshot_id.into();
+        }
 
         let res = self.client
-            .put("https://api.spotify.com/v1/me/player/play")
+            .put(self.url(&format!("/v1/playlists/{}/tracks", playlist_id)))
             .bearer_auth(&self.access_token)
             .json(&body)
             .send()
             .await?;
 
         if !res.status().is_success() {
-            return Err(format!("Failed to play playlist: {}", res.status()).into());
+            return Err(api_error("Failed to reorder playlist", res).await);
+        }
+
+        let snapshot: SnapshotResponse = res.json().await?;
+        Ok(snapshot.snapshot_id)
+    }
+
+    /// プレイリストを再生（start_uri を渡すとその曲から、device_id を渡すとそのデバイスで）
+    pub async fn play_playlist(
+        &self,
+        playlist_id: &str,
+        start_uri: Option<&str>,
+        device_id: Option<&str>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let context_uri = format!("spotify:playlist:{}", playlist_id);
+        let offset = start_uri.map_or(PlayOffset::Position(0), PlayOffset::Uri);
+        self.play_context(&context_uri, Some(offset), 0, device_id).await
+    }
+
+    /// アルバムがライブラリに保存されているか
+    pub async fn is_album_saved(&self, album_id: &str) -> Result<bool, Box<dyn std::error::Error>> {
+        let request = self.client
+            .get(self.url("/v1/me/albums/contains"))
+            .bearer_auth(&self.access_token)
+            .query(&[("ids", album_id)]);
+        let res = self.send_with_retry(request).await?;
+
+        if !res.status().is_success() {
+            return Err(scope_error("Failed to check saved albums", "user-library-read", res).await);
+        }
+
+        let saved: Vec<bool> = res.json().await?;
+        Ok(saved.first().copied().unwrap_or(false))
+    }
+
+    /// アルバムをライブラリに保存する（saved が false なら削除）
+    pub async fn set_album_saved(&self, album_id: &str, saved: bool) -> Result<(), Box<dyn std::error::Error>> {
+        let request = if saved {
+            self.client.put(self.url("/v1/me/albums"))
+        } else {
+            self.client.delete(self.url("/v1/me/albums"))
+        };
+        let res = request
+            .bearer_auth(&self.access_token)
+            .query(&[("ids", album_id)])
+            .header("Content-Length", "0")
+            .send()
+            .await?;
+
+        if !res.status().is_success() {
+            let action = if saved { "Failed to save album" } else { "Failed to remove album" };
+            return Err(scope_error(action, "user-library-modify", res).await);
         }
 
         Ok(())
     }
+
+    pub async fn save_album(&self, album_id: &str) -> Result<(), Box<dyn std::error::Error>> {
+        self.set_album_saved(album_id, true).await
+    }
+
+    pub async fn remove_saved_album(&self, album_id: &str) -> Result<(), Box<dyn std::error::Error>> {
+        self.set_album_saved(album_id, false).await
+    }
+
+    /// プレイリストを 1 件取得（一覧には無いフォロワー数や最新の snapshot_id を含む）
+    pub async fn get_playlist(&self, playlist_id: &str) -> Result<Playlist, Box<dyn std::error::Error>> {
+        let request = self.client
+            .get(self.url(&format!("/v1/playlists/{}", playlist_id)))
+            .bearer_auth(&self.access_token)
+            .query(&[
+                ("market", "US"),
+                ("fields", "id,name,tracks(total),images,owner(id,display_name),description,collaborative,snapshot_id,followers(total)"),
+            ]);
+        let res = self.send_with_retry(request).await?;
+
+        if !res.status().is_success() {
+            return Err(api_error("Failed to fetch playlist", res).await);
+        }
+
+        Ok(res.json().await?)
+    }
+
+    /// 再生元（プレイリスト・アルバム・アーティスト）の名前
+    pub async fn get_context_name(&self, context_uri: &str) -> Result<String, Box<dyn std::error::Error>> {
+        let kind = context_kind(context_uri)
+            .ok_or_else(|| format!("Unsupported context URI: {}", context_uri))?;
+        let id = context_uri.rsplit(':').next().unwrap_or_default();
+        let mut request = self.client
+            .get(self.url(&format!("/v1/{}s/{}", kind, id)))
+            .bearer_auth(&self.access_token);
+        // プレイリストは曲まで返ってきて重いので名前だけにする
+        if kind == "playlist" {
+            request = request.query(&[("fields", "name")]);
+        }
+        let res = self.send_with_retry(request).await?;
+
+        if !res.status().is_success() {
+            return Err(api_error(&format!("Failed to fetch {}", kind), res).await);
+        }
+
+        let response: NameResponse = res.json().await?;
+        Ok(response.name)
+    }
+
+    /// ログイン中のユーザーがプレイリストをフォローしているか
+    pub async fn is_following_playlist(&self, playlist_id: &str) -> Result<bool, Box<dyn std::error::Error>> {
+        let mut request = self.client
+            .get(self.url(&format!("/v1/playlists/{}/followers/contains", playlist_id)))
+            .bearer_auth(&self.access_token);
+        if let Some(user) = &self.current_user {
+            request = request.query(&[("ids", user.id.as_str())]);
+        }
+        let res = self.send_with_retry(request).await?;
+
+        if !res.status().is_success() {
+            return Err(api_error("Failed to check playlist follow state", res).await);
+        }
+
+        let following: Vec<bool> = res.json().await?;
+        Ok(following.first().copied().unwrap_or(false))
+    }
+
+    /// プレイリストをフォローする（following が false ならフォロー解除）
+    pub async fn set_playlist_following(&self, playlist_id: &str, following: bool) -> Result<(), Box<dyn std::error::Error>> {
+        let url = self.url(&format!("/v1/playlists/{}/followers", playlist_id));
+        let request = if following {
+            self.client.put(url).json(&serde_json::json!({ "public": true }))
+        } else {
+            self.client.delete(url).header("Content-Length", "0")
+        };
+        let res = request.bearer_auth(&self.access_token).send().await?;
+
+        if !res.status().is_success() {
+            let action = if following { "Failed to follow playlist" } else { "Failed to unfollow playlist" };
+            return Err(scope_error(action, "playlist-modify-public", res).await);
+        }
+
+        Ok(())
+    }
+
+    pub async fn follow_playlist(&self, playlist_id: &str) -> Result<(), Box<dyn std::error::Error>> {
+        self.set_playlist_following(playlist_id, true).await
+    }
+
+    pub async fn unfollow_playlist(&self, playlist_id: &str) -> Result<(), Box<dyn std::error::Error>> {
+        self.set_playlist_following(playlist_id, false).await
+    }
+
+    /// アルバムを指定した曲から再生する
+    pub async fn play_album(
+        &self,
+        album_id: &str,
+        start_uri: Option<&str>,
+        device_id: Option<&str>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let context_uri = format!("spotify:album:{}", album_id);
+        let offset = start_uri.map_or(PlayOffset::Position(0), PlayOffset::Uri);
+        self.play_context(&context_uri, Some(offset), 0, device_id).await
+    }
+
+    /// 1 曲だけ再生する（コンテキストが無いので終わったら止まる）
+    pub async fn play_track(
+        &self,
+        uri: &str,
+        position_ms: i64,
+        device_id: Option<&str>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let body = serde_json::json!({ "uris": [uri], "position_ms": position_ms });
+
+        let mut req = self.client
+            .put(self.url("/v1/me/player/play"))
+            .bearer_auth(&self.access_token)
+            .json(&body);
+        if let Some(device_id) = device_id {
+            req = req.query(&[("device_id", device_id)]);
+        }
+        let res = req.send().await?;
+
+        if !res.status().is_success() {
+            return Err(api_error("Failed to play track", res).await);
+        }
+
+        Ok(())
+    }
+
+    /// プレイリスト・アルバム・アーティストの URI を再生する
+    ///
+    /// アーティストは曲順が無いので offset を指定するとエラーにする（API も受け付けない）。
+    /// position_ms は offset の曲の中で再生を始める位置。
+    pub async fn play_context(
+        &self,
+        context_uri: &str,
+        offset: Option<PlayOffset<'_>>,
+        position_ms: i64,
+        device_id: Option<&str>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let kind = context_kind(context_uri)
+            .ok_or_else(|| format!("Unsupported context URI: {}", context_uri))?;
+
+        let mut body = serde_json::json!({
+            "context_uri": context_uri,
+            "position_ms": position_ms
+        });
+        match offset {
+            Some(_) if kind == "artist" => {
+                return Err("Artist contexts can't start at an offset".into());
+            }
+            Some(PlayOffset::Position(position)) => body["offset"] = serde_json::json!({ "position": position }),
+            Some(PlayOffset::Uri(uri)) => body["offset"] = serde_json::json!({ "uri": uri }),
+            None => {}
+        }
+
+        let mut req = self.client
+            .put(self.url("/v1/me/player/play"))
+            .bearer_auth(&self.access_token)
+            .json(&body);
+        // 指定がなければアクティブなデバイスで再生
+        if let Some(device_id) = device_id {
+            req = req.query(&[("device_id", device_id)]);
+        }
+        let res = req.send().await?;
+
+        if !res.status().is_success() {
+            return Err(api_error(&format!("Failed to play {}", kind), res).await);
+        }
+
+        Ok(())
+    }
+}
+
+/// Web API が失敗時に返す本文 `{ "error": { "status", "message", "reason" } }`
+#[derive(Deserialize, Debug)]
+struct ErrorResponse {
+    error: ErrorBody,
+}
+
+#[derive(Deserialize, Debug)]
+struct ErrorBody {
+    message: Option<String>,
+    /// PREMIUM_REQUIRED や NO_ACTIVE_DEVICE など（プレイヤー系の API だけ）
+    reason: Option<String>,
+}
+
+/// 失敗したレスポンスを「{action}: {status} - {message} ({reason})」のエラーにする
+async fn api_error(action: &str, res: Response) -> Box<dyn std::error::Error> {
+    let status = res.status();
+    let body = res.json::<ErrorResponse>().await.ok().map(|response| response.error);
+    let mut message = format!("{}: {}", action, status);
+    if let Some(detail) = body.as_ref().and_then(|body| body.message.as_deref()) {
+        message.push_str(&format!(" - {}", detail));
+    }
+    if let Some(reason) = body.as_ref().and_then(|body| body.reason.as_deref()) {
+        message.push_str(&format!(" ({})", reason));
+    }
+    Box::new(ApiError::new(status, message))
+}
+
+/// `spotify:{kind}:{id}` の kind（再生できるコンテキストでなければ None）
+fn context_kind(context_uri: &str) -> Option<&str> {
+    let mut parts = context_uri.split(':');
+    let (Some("spotify"), Some(kind), Some(id), None) = (parts.next(), parts.next(), parts.next(), parts.next()) else {
+        return None;
+    };
+    let supported = matches!(kind, "playlist" | "album" | "artist");
+    (supported && !id.is_empty()).then_some(kind)
+}
+
+/// スコープの足りないトークンでの 403 は、認可し直せば直ることを伝える
+async fn scope_error(action: &str, scope: &str, res: Response) -> Box<dyn std::error::Error> {
+    if res.status() == StatusCode::FORBIDDEN {
+        return format!("{}: missing the {} permission; restart rs-pod to authorize it", action, scope).into();
+    }
+    api_error(action, res).await
+}
+
+/// Retry-After ヘッダ（秒）を読む
+fn retry_after(res: &Response) -> Duration {
+    res.headers()
+        .get(RETRY_AFTER)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.trim().parse::<u64>().ok())
+        .map(Duration::from_secs)
+        .unwrap_or(DEFAULT_RETRY_AFTER)
+        .min(MAX_RETRY_AFTER)
 }
@@ -1,24 +1,109 @@
-use crate::api::oauth::SpotifyOAuth;
-use crate::api::spotify::{SpotifyClient, SkipDirection, Playlist};
-use crate::utils::format_time;
+use crate::action::{self, Action};
+use crate::api::error::ErrorKind;
+use crate::api::oauth::ProvidedToken;
+use crate::api::spotify::{
+    Album, Device, PlayOffset, PlayingType, Playlist, RepeatState, SkipDirection, SpotifyClient, SpotifyPlayer, Track,
+};
+use crate::config::{Config, PlayMode, SleepAction};
+use crate::keymap::{self, KeyBinding};
+#[cfg(all(feature = "mpris", target_os = "linux"))]
+use crate::mpris::{Mpris, MprisCommand};
+use crate::nav::NavHistory;
+use crate::now_playing_file::NowPlayingFile;
+use crate::album_art::{self, AlbumArt};
+use crate::album_view::AlbumView;
+use crate::continue_queue::ContinueQueue;
+use crate::playlist_picker::PlaylistPicker;
+use crate::search::Search;
+use crate::playlist_view::PlaylistView;
+use crate::queue_preview::QueuePreview;
+use crate::resume::ResumePoints;
+use crate::session::{LastPlayed, Session};
+use crate::state_socket::StateSocket;
+use crate::theme;
+use crate::thumbnails::Thumbnails;
+use crate::utils::{
+    format_duration_long, format_time, fuzzy_match, join_with_overflow, local_time_of_day, locale_is_utf8, marquee,
+    move_selection, pad_to_width, progress_percent, track_columns, truncate_with_ellipsis, Movement,
+};
 use color_eyre::Result;
-use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind};
+use rand::Rng;
+use crossterm::{
+    event::{
+        self, DisableFocusChange, EnableFocusChange, Event, KeyCode, KeyEvent, KeyEventKind,
+        KeyModifiers, MouseButton, MouseEvent, MouseEventKind,
+    },
+    execute,
+};
 use ratatui::{
     buffer::Buffer,
-    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    layout::{Alignment, Constraint, Direction, Layout, Margin, Rect},
     style::{Color, Style, Stylize},
     symbols::border,
-    text::Line,
-    widgets::{Block, Gauge, Paragraph, Widget, List, ListItem, ListState},
+    text::{Line, Text},
+    widgets::{Block, Clear, Gauge, HighlightSpacing, Paragraph, Widget, List, ListItem, ListState},
     DefaultTerminal, Frame,
 };
-use reqwest::Client;
-use std::{io, time::Duration};
+use std::{
+    collections::{HashMap, HashSet},
+    io,
+    time::{Duration, Instant, SystemTime},
+};
+use serde::{Deserialize, Serialize};
+use tokio::{sync::oneshot, task::JoinHandle};
+use unicode_width::UnicodeWidthStr;
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+/// 保存位置がこれ以上先にある場合だけ再開を提案する
+const RESUME_THRESHOLD_MS: i64 = 30_000;
+
+/// ステータス行の通知を消すまでの時間
+const STATUS_TIMEOUT: Duration = Duration::from_secs(4);
+
+/// キー入力を待つ時間の上限（この間隔で画面と再生状態を更新する）
+const EVENT_POLL_TIMEOUT: Duration = Duration::from_millis(100);
+
+/// フォーカスが外れている間のポーリング間隔
+const UNFOCUSED_POLL_INTERVAL: Duration = Duration::from_secs(15);
+
+/// 再生画面をすべて並べるのに必要な高さ（これより低ければ 1 行の表示にする）
+const NOW_PLAYING_MIN_HEIGHT: u16 = 17;
+/// 1 行表示のプログレスバーの最大幅
+const COMPACT_BAR_WIDTH: usize = 20;
+/// ジャケット画像を出すのに最低限必要な行数
+const ALBUM_ART_MIN_HEIGHT: u16 = 4;
+/// これより狭い枠には何も描かない
+const MIN_RENDER_WIDTH: u16 = 2;
+
+/// 再生画面で曲名の後ろに付けるバッジ
+const EXPLICIT_BADGE: &str = " E ";
+
+/// +/- で変える音量の幅
+const VOLUME_STEP: u8 = 10;
+/// プレイリスト一覧のカバー画像の大きさ（セルは縦長なので幅を高さの 2 倍にすると正方形に見える）
+const THUMBNAIL_WIDTH: u16 = 4;
+const THUMBNAIL_HEIGHT: u16 = 2;
+/// カバー画像を同時に取得する数
+const IMAGE_PREFETCH_CONCURRENCY: usize = 4;
+/// スリープタイマーで音量を下げ始める残り時間
+const SLEEP_FADE: Duration = Duration::from_secs(60);
+/// フェード中に音量を変える最小の幅（API を叩きすぎないように）
+const SLEEP_FADE_STEP: u8 = 5;
+/// 音量バーの目盛りの数
+const VOLUME_BAR_WIDTH: usize = 10;
+
+/// 読み込み中のスピナー（描画ごとに1コマ進める）
+const SPINNER_FRAMES: [&str; 10] = ["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub enum Page {
     PlaylistList,
+    PlaylistTracks,
     NowPlaying,
+    Devices,
+    QueuePreview,
+    NewReleases,
+    AlbumTracks,
+    Search,
 }
 
 pub struct App {
@@ -26,87 +111,730 @@ pub struct App {
     exit: bool,
     current_track_name: Option<String>,
     current_page: Page,
+    nav_history: NavHistory<Page>,
     playlists: Vec<Playlist>,
+    /// 絞り込み中なら入力中の文字列
+    playlist_filter: Option<String>,
+    /// 新しく作るプレイリストの名前を入力中
+    new_playlist_name: Option<String>,
+    /// 単曲の再生が終わったら続けて流す曲（config.auto_advance のとき）
+    continue_queue: ContinueQueue,
+    /// スリープタイマーの分数を入力中
+    sleep_minutes: Option<String>,
+    /// スリープタイマーで一時停止する時刻
+    sleep_at: Option<Instant>,
+    /// フェードアウトを始める前の音量（止めたら戻す）
+    sleep_volume: Option<u8>,
+    /// スリープタイマーで止めた後の時計の画面を出しているか（sleep_action が clock のとき）
+    sleep_clock: bool,
+    /// 移動キーの前に打った回数（5j など）
+    pending_count: Option<usize>,
+    /// 表示中のプレイリストの playlists 内の位置（playlist_state はこのリストの位置）
+    filtered_playlists: Vec<usize>,
     playlist_state: ListState,
+    config: Config,
+    resume_points: ResumePoints,
+    /// 再開を提案中のエピソード (episode_id, position_ms)
+    resume_offer: Option<(String, i64)>,
+    /// 前回終了したときの曲の続きから再生する提案（何かを再生し始めたら取り下げる）
+    last_played_offer: Option<LastPlayed>,
+    /// spotify_player.progress_ms を最後に更新した時刻（補間用）
+    last_progress_update: Instant,
+    /// リトライしても再生状態を取得できなかった理由（表示が古い。取得できたら消す）
+    playback_error: Option<ErrorKind>,
+    /// 端末にフォーカスがあるか（FocusLost/FocusGained で更新）
+    focused: bool,
+    /// 次のループで間隔を待たずに再生状態を取得する
+    refresh_requested: bool,
+    /// 長い名前の横スクロール位置
+    marquee_tick: usize,
+    /// 開いているプレイリストの曲一覧
+    playlist_view: Option<PlaylistView>,
+    /// 再生先に選べるデバイス（d で取得）
+    devices: Vec<Device>,
+    device_state: ListState,
+    /// キューに追加する前の確認リスト
+    queue_preview: Option<QueuePreview>,
+    /// 新着アルバム（n で取得）
+    new_releases: Vec<Album>,
+    new_releases_state: ListState,
+    /// 新着から開いたアルバムの曲一覧
+    album_view: Option<AlbumView>,
+    /// 曲の検索（s で開く。ページを離れても入力は残す）
+    search: Search,
+    /// 表示中のモーダル（どのキーでも閉じる）
+    overlay: Option<Overlay>,
+    /// 再生中の曲を追加するプレイリストの選択
+    playlist_picker: Option<PlaylistPicker>,
+    /// 重複確認のために取得したプレイリストの曲（id → uri、このセッションの間使い回す）
+    playlist_uris: HashMap<String, HashSet<String>>,
+    /// 再生元の URI → 名前（取得できなかったものは None で、ポーリングのたびに取り直さない）
+    context_names: HashMap<String, Option<String>>,
+    /// 操作結果の通知（一定時間で消える）
+    status_message: Option<StatusMessage>,
+    /// バックグラウンドで取得中のプレイリスト
+    playlists_task: Option<JoinHandle<Result<Vec<Playlist>, String>>>,
+    /// バックグラウンドで取得中の再生状態（再試行の間も画面とキー入力を止めない）
+    playback_task: Option<JoinHandle<Result<SpotifyPlayer, ErrorKind>>>,
+    /// プレイリストのカバー画像の先読み
+    image_prefetch_task: Option<JoinHandle<()>>,
+    /// R で取り直したプレイリストが届いたら「Refreshed」を出す
+    playlists_refreshing: bool,
+    /// プレイリストの取得に失敗したときのエラー（r で再試行）
+    playlists_error: Option<String>,
+    /// 前回選んでいたプレイリスト（読み込みが終わったら選択し直す）
+    restore_playlist_id: Option<String>,
+    spinner_frame: usize,
+    /// 区切り線（幅が変わったときだけ作り直す）
+    separator: String,
+    /// 再生中の曲のジャケット画像（無効なら None）
+    album_art: Option<AlbumArt>,
+    /// プレイリスト一覧のカバー画像（config.playlist_thumbnails が false なら None）
+    thumbnails: Option<Thumbnails>,
+    /// プレイリスト一覧の 1 項目の高さ（カバー画像を出すときは 2 行）
+    playlist_row_height: u16,
+    /// 直近の描画でのプレイリストの枠内とプログレスバーの位置（マウス操作用）
+    playlist_list_area: Rect,
+    /// 直近に描画したリストの表示行数（PageUp / PageDown の移動量）
+    list_page_size: usize,
+    progress_area: Rect,
+    /// 設定されていれば再生中の曲を書き出すファイル
+    now_playing_file: Option<NowPlayingFile>,
+    /// X11 では持ち主が生きている間しか貼り付けられないので使い回す（初回のコピーで作る）
+    #[cfg(feature = "clipboard")]
+    clipboard: Option<arboard::Clipboard>,
+    /// 設定されていれば再生状態を配信するソケット（終了時に閉じる）
+    state_socket: Option<StateSocket>,
+    /// メディアキーやデスクトップからの操作（run の中で作る）
+    #[cfg(all(feature = "mpris", target_os = "linux"))]
+    mpris: Option<Mpris>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Overlay {
+    /// キー割り当てのヘルプ
+    Help,
+    /// 再生中の曲のアーティスト全員
+    Artists,
+    /// 再生中の曲のアルバムや人気度
+    TrackInfo,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum StatusKind {
+    Success,
+    Error,
+}
+
+#[derive(Debug)]
+struct StatusMessage {
+    text: String,
+    kind: StatusKind,
+    shown_at: Instant,
 }
 
 impl App {
-    pub async fn new() -> Result<Self> {
-        //1. get oauth
-        let access_token = SpotifyOAuth::init()
+    pub async fn new(provided_token: Option<ProvidedToken>) -> Result<Self> {
+        let config = Config::load().map_err(|e| color_eyre::eyre::eyre!("{}", e))?;
+
+        //1. get oauth（トークンが渡されていればブラウザでの認可は省略）
+        let spotify_client = SpotifyClient::connect(&config, provided_token)
             .await
             .map_err(|e| color_eyre::eyre::eyre!("{}", e))?;
 
         // init spotify_client and get current song
-        let spotify_client = SpotifyClient::new(Client::new(), &access_token)
+        let spotify_client = spotify_client
             .init()
             .await
             .map_err(|e| color_eyre::eyre::eyre!("{}", e))?;
 
+        let mut app = Self::from_parts(spotify_client, config, Session::load());
+        // 描画のたびに問い合わせないよう、使える画像の形式は起動時に一度だけ調べる
+        if app.config.album_art || app.config.playlist_thumbnails {
+            let picker = album_art::detect_picker();
+            if app.config.album_art {
+                app.album_art = Some(AlbumArt::new(picker));
+            }
+            if app.config.playlist_thumbnails {
+                app.thumbnails = Some(Thumbnails::new(Some(picker)));
+            }
+        }
+        Ok(app)
+    }
+
+    /// 接続済みのクライアントから作る（前回のセッションは復元しない）
+    ///
+    /// 認可やセッションファイルを使わずに動かせるので、テストや埋め込みで使う。
+    /// プレイリストの取得はすぐに始まる。
+    pub fn with_client(spotify_client: SpotifyClient, config: Config) -> Self {
+        Self::from_parts(spotify_client, config, Session::default())
+    }
+
+    fn from_parts(spotify_client: SpotifyClient, config: Config, session: Session) -> Self {
+        theme::init(config.theme.palette);
+        keymap::init(&config.keybindings);
         let current_track_name = spotify_client
             .spotify_player
             .item
             .as_ref()
             .map(|track| track.name.clone());
 
-        // プレイリストを取得
-        let playlists = spotify_client
-            .get_user_playlists()
-            .await
-            .unwrap_or_default();
+        let resume_points = if config.resume_episodes {
+            ResumePoints::load()
+        } else {
+            ResumePoints::default()
+        };
 
-        let mut playlist_state = ListState::default();
-        if !playlists.is_empty() {
-            playlist_state.select(Some(0));
-        }
+        // 画像の形式を調べるまでは代わりの四角を出す
+        let thumbnails = config.playlist_thumbnails.then(|| Thumbnails::new(None));
+
+        let now_playing_file = config
+            .now_playing_file
+            .clone()
+            .map(|path| NowPlayingFile::new(path, config.now_playing_format.clone()));
+
+        // ソケットを開けなくても再生はできるので起動は続ける
+        let state_socket = match config.state_socket.clone() {
+            Some(path) => match StateSocket::bind(path, config.state_socket_port) {
+                Ok(socket) => Some(socket),
+                Err(e) => {
+                    tracing::warn!("Failed to open state socket: {}", e);
+                    None
+                }
+            },
+            None => None,
+        };
+
+        // 曲一覧やキュー確認は中身を復元できないのでプレイリスト一覧から始める
+        let current_page = match session.page {
+            // どこでも Spotify が開かれていなければ、再生画面で開き方とデバイスの選び方を案内する
+            _ if spotify_client.spotify_player.device.is_none() => Page::NowPlaying,
+            Some(
+                Page::PlaylistTracks
+                | Page::Devices
+                | Page::QueuePreview
+                | Page::NewReleases
+                | Page::AlbumTracks
+                | Page::Search,
+            )
+            | None => Page::PlaylistList,
+            Some(page) => page,
+        };
 
-        Ok(Self {
+        // 前回の曲は、しばらく経っていたら提案しない（何か再生中のときも）
+        let max_age = Duration::from_secs(config.resume_last_played_hours * 60 * 60);
+        let last_played_offer = session.last_played.filter(|last| {
+            config.resume_last_played_hours > 0
+                && last.is_recent(max_age)
+                && !spotify_client.spotify_player.is_playing
+        });
+
+        let mut app = Self {
             spotify_client,
             exit: false,
             current_track_name,
-            current_page: Page::PlaylistList,
-            playlists,
-            playlist_state,
-        })
+            current_page,
+            nav_history: NavHistory::default(),
+            playlists: Vec::new(),
+            playlist_filter: None,
+            new_playlist_name: None,
+            continue_queue: ContinueQueue::default(),
+            sleep_minutes: None,
+            sleep_at: None,
+            sleep_volume: None,
+            sleep_clock: false,
+            pending_count: None,
+            filtered_playlists: Vec::new(),
+            playlist_state: ListState::default(),
+            config,
+            resume_points,
+            resume_offer: None,
+            last_played_offer,
+            last_progress_update: Instant::now(),
+            playback_error: None,
+            focused: true,
+            refresh_requested: false,
+            marquee_tick: 0,
+            playlist_view: None,
+            devices: Vec::new(),
+            device_state: ListState::default(),
+            queue_preview: None,
+            new_releases: Vec::new(),
+            new_releases_state: ListState::default(),
+            album_view: None,
+            search: Search::default(),
+            overlay: None,
+            playlist_picker: None,
+            playlist_uris: HashMap::new(),
+            context_names: HashMap::new(),
+            status_message: None,
+            playlists_task: None,
+            playback_task: None,
+            image_prefetch_task: None,
+            playlists_refreshing: false,
+            playlists_error: None,
+            restore_playlist_id: session.playlist_id,
+            spinner_frame: 0,
+            separator: String::new(),
+            album_art: None,
+            thumbnails,
+            playlist_row_height: 1,
+            playlist_list_area: Rect::default(),
+            list_page_size: 0,
+            progress_area: Rect::default(),
+            now_playing_file,
+            state_socket,
+            #[cfg(feature = "clipboard")]
+            clipboard: None,
+            #[cfg(all(feature = "mpris", target_os = "linux"))]
+            mpris: None,
+        };
+        // プレイリストは最初の描画の後に届く
+        app.load_playlists();
+        app.write_now_playing();
+        app.resume_offer = app.resume_offer_for(&app.spotify_client.spotify_player);
+        app
     }
 
     pub async fn run(&mut self, terminal: &mut DefaultTerminal) -> Result<()> {
+        // MPRIS のサーバーは Send ではないので LocalSet の上で動かす
+        tokio::task::LocalSet::new()
+            .run_until(self.run_loop(terminal))
+            .await
+    }
+
+    async fn run_loop(&mut self, terminal: &mut DefaultTerminal) -> Result<()> {
+        // D-Bus のセッションが無い環境ではそのまま MPRIS 無しで動く
+        #[cfg(all(feature = "mpris", target_os = "linux"))]
+        {
+            self.mpris = Mpris::new().await.ok();
+        }
+
         let mut last_update = tokio::time::Instant::now();
-        let update_interval = Duration::from_secs(1);
+        let update_interval = self.config.poll_interval();
+
+        // フォーカスイベントを送らない端末もあるので設定で有効にしたときだけ
+        if self.config.slow_poll_when_unfocused {
+            execute!(io::stdout(), EnableFocusChange)?;
+        }
+
+        // SIGINT / SIGTERM でも q と同じ後片付けをしてから終わる
+        let (signal_tx, mut shutdown) = oneshot::channel();
+        let signal_task = tokio::spawn(async move {
+            shutdown_signal().await;
+            let _ = signal_tx.send(());
+        });
 
         while !self.exit {
             terminal.draw(|frame| self.draw(frame))?;
 
             // 非ブロッキングでイベントを処理
             self.handle_events().await?;
+            self.process_queue_preview().await;
+            self.check_sleep_timer().await;
+            self.poll_playlists().await;
+            self.search.poll(&self.spotify_client).await;
+            if let Some(album_art) = self.album_art.as_mut() {
+                album_art.update(&self.spotify_client, self.spotify_client.spotify_player.item.as_ref());
+                album_art.poll().await;
+            }
+            if let Some(thumbnails) = self.thumbnails.as_mut() {
+                thumbnails.poll().await;
+            }
+            #[cfg(all(feature = "mpris", target_os = "linux"))]
+            self.process_mpris().await;
+            if shutdown.try_recv().is_ok() {
+                self.exit();
+            }
+            // 認証などで出た警告は stdout ではなくステータス行に出す
+            for text in crate::notices::take() {
+                self.set_status(StatusKind::Error, text);
+            }
+
+            if self
+                .status_message
+                .as_ref()
+                .is_some_and(|status| status.shown_at.elapsed() >= STATUS_TIMEOUT)
+            {
+                self.status_message = None;
+            }
 
-            // 1秒ごとに更新
-            if last_update.elapsed() >= update_interval {
-                if let Ok(player) = self.spotify_client.get_current_playback().await {
+            // 設定の間隔で更新（フォーカスが外れている間は間隔を延ばす）
+            let interval = if self.focused {
+                update_interval
+            } else {
+                UNFOCUSED_POLL_INTERVAL.max(update_interval)
+            };
+            // 429 で指定された時刻までは取得しない
+            if self.playback_task.is_none()
+                && self.spotify_client.rate_limited_until().is_none()
+                && (self.refresh_requested || last_update.elapsed() >= interval)
+            {
+                self.refresh_requested = false;
+                self.start_playback_poll();
+            }
+            if self.playback_task.as_ref().is_some_and(JoinHandle::is_finished)
+                && let Some(task) = self.playback_task.take()
+            {
+                let playback = task.await.unwrap_or(Err(ErrorKind::Api));
+                self.playback_error = playback.as_ref().err().copied();
+                if let Ok(player) = playback {
                     // Check if track changed
                     let new_track_name = player.item.as_ref().map(|t| t.name.clone());
                     if new_track_name != self.current_track_name {
                         self.current_track_name = new_track_name;
                     }
+                    self.update_resume_points(&player);
+                    if player.is_playing {
+                        self.last_played_offer = None;
+                    }
+                    // 止まるのを見逃さないように、2 回分のポーリングの間に終わる位置なら終わり際とみなす
+                    let end_margin_ms = interval.as_millis() as i64 * 2 + 1000;
+                    let next = self
+                        .continue_queue
+                        .next_after(&self.spotify_client.spotify_player, &player, end_margin_ms);
                     self.spotify_client.spotify_player = player;
+                    self.last_progress_update = Instant::now();
+                    if let Some(track) = next {
+                        let result = self.spotify_client.play_track(&track.uri, 0, None).await;
+                        self.report(result, Some(format!("Playing next: {}", track.name)));
+                        self.force_refresh();
+                    }
+                    self.resolve_context_name().await;
+                    self.write_now_playing();
+                    #[cfg(all(feature = "mpris", target_os = "linux"))]
+                    if let Some(mpris) = self.mpris.as_mut() {
+                        mpris.update(&self.spotify_client.spotify_player).await;
+                    }
                 }
                 last_update = tokio::time::Instant::now();
             }
         }
+
+        signal_task.abort();
+        if let Some(task) = self.playlists_task.take() {
+            task.abort();
+        }
+        if let Some(task) = self.playback_task.take() {
+            task.abort();
+        }
+        if let Some(task) = self.image_prefetch_task.take() {
+            task.abort();
+        }
+        if self.config.slow_poll_when_unfocused {
+            execute!(io::stdout(), DisableFocusChange)?;
+        }
+        if self.config.resume_episodes {
+            let _ = self.resume_points.save();
+        }
+        // 何も再生していなければ、まだ使っていない前回の曲を引き継ぐ
+        let player = &self.spotify_client.spotify_player;
+        let last_played = match &player.item {
+            Some(track) if !self.is_ad() => Some(LastPlayed::new(
+                track.uri.clone(),
+                track.name.clone(),
+                player.context.as_ref().map(|context| context.uri.clone()),
+                self.interpolated_progress_ms(),
+            )),
+            _ => self.last_played_offer.take(),
+        };
+        // 読み込み前に終了したときは前回の選択を引き継ぐ
+        let session = Session {
+            page: Some(self.current_page),
+            playlist_id: self
+                .selected_playlist()
+                .map(|playlist| playlist.id.clone())
+                .or(self.restore_playlist_id.take()),
+            last_played,
+        };
+        let _ = session.save();
         Ok(())
     }
 
+    /// 再生元の名前をまだ知らなければ調べる（読み込み済みのプレイリストなら取得しない）
+    async fn resolve_context_name(&mut self) {
+        let Some(uri) = self.spotify_client.spotify_player.context.as_ref().map(|context| context.uri.clone()) else {
+            return;
+        };
+        if self.context_names.contains_key(&uri) {
+            return;
+        }
+        let loaded = self
+            .playlists
+            .iter()
+            .find(|playlist| uri == format!("spotify:playlist:{}", playlist.id))
+            .map(|playlist| playlist.name.clone());
+        let name = if let Some(name) = loaded {
+            Some(name)
+        } else if uri.ends_with(":collection") {
+            // 「いいね」した曲（spotify:user:{id}:collection）
+            Some("Liked Songs".to_string())
+        } else {
+            match self.spotify_client.get_context_name(&uri).await {
+                Ok(name) => Some(name),
+                Err(e) => {
+                    tracing::debug!(error = %e, uri, "Failed to resolve context name");
+                    None
+                }
+            }
+        };
+        self.context_names.insert(uri, name);
+    }
+
+    /// 再生元の名前（単曲の再生や名前が分からないときは None）
+    fn context_name(&self) -> Option<&str> {
+        let context = self.spotify_client.spotify_player.context.as_ref()?;
+        self.context_names.get(&context.uri)?.as_deref()
+    }
+
+    /// プレイリストの取得をバックグラウンドで始める
+    fn load_playlists(&mut self) {
+        let client = self.spotify_client.clone();
+        self.playlists_error = None;
+        self.playlists_task = Some(tokio::spawn(async move {
+            client.get_user_playlists().await.map_err(|e| e.to_string())
+        }));
+    }
+
+    /// 再生状態の取得をバックグラウンドで始める
+    fn start_playback_poll(&mut self) {
+        let client = self.spotify_client.clone();
+        self.playback_task = Some(tokio::spawn(async move {
+            client
+                .get_current_playback()
+                .await
+                .map_err(|e| ErrorKind::classify(e.as_ref()))
+        }));
+    }
+
+    /// 取得が終わっていたらリストに反映する
+    async fn poll_playlists(&mut self) {
+        if !self
+            .playlists_task
+            .as_ref()
+            .is_some_and(JoinHandle::is_finished)
+        {
+            return;
+        }
+        let Some(task) = self.playlists_task.take() else {
+            return;
+        };
+        self.finish_playlists(task.await);
+    }
+
+    /// 読み込み中のプレイリストが届くまで待って反映する
+    pub async fn wait_for_playlists(&mut self) {
+        if let Some(task) = self.playlists_task.take() {
+            self.finish_playlists(task.await);
+        }
+    }
+
+    fn finish_playlists(&mut self, result: Result<Result<Vec<Playlist>, String>, tokio::task::JoinError>) {
+        match result {
+            Ok(Ok(playlists)) => {
+                self.playlists = playlists;
+                self.refilter_playlists();
+                self.prefetch_playlist_images();
+                // 前回のプレイリストが無くなっていたら先頭のまま
+                if let Some(id) = self.restore_playlist_id.take()
+                    && let Some(position) = self
+                        .filtered_playlists
+                        .iter()
+                        .position(|&index| self.playlists[index].id == id)
+                {
+                    self.playlist_state.select(Some(position));
+                }
+                if std::mem::take(&mut self.playlists_refreshing) {
+                    self.set_status(StatusKind::Success, "Refreshed".to_string());
+                }
+            }
+            Ok(Err(e)) => self.playlists_error = Some(e),
+            Err(e) => self.playlists_error = Some(e.to_string()),
+        }
+        self.playlists_refreshing = false;
+    }
+
+    /// カバー画像をバックグラウンドでキャッシュに入れておく（取り直したときは前の分を止める）
+    fn prefetch_playlist_images(&mut self) {
+        if let Some(task) = self.image_prefetch_task.take() {
+            task.abort();
+        }
+        let urls: Vec<String> = self
+            .playlists
+            .iter()
+            .filter_map(|playlist| playlist.images.first())
+            .map(|image| image.url.clone())
+            .collect();
+        let client = self.spotify_client.clone();
+        self.image_prefetch_task = Some(tokio::spawn(async move {
+            client.prefetch_images(urls, IMAGE_PREFETCH_CONCURRENCY).await;
+        }));
+    }
+
+    /// 絞り込み条件に合うプレイリストを選び直し、選択位置を範囲内に収める
+    fn refilter_playlists(&mut self) {
+        let query = self.playlist_filter.as_deref().unwrap_or("");
+        self.filtered_playlists = self
+            .playlists
+            .iter()
+            .enumerate()
+            .filter(|(_, playlist)| fuzzy_match(query, &playlist.name))
+            .map(|(i, _)| i)
+            .collect();
+
+        let selected = match self.filtered_playlists.len() {
+            0 => None,
+            len => Some(self.playlist_state.selected().unwrap_or(0).min(len.saturating_sub(1))),
+        };
+        self.playlist_state.select(selected);
+    }
+
+    /// 選択中のプレイリストの playlists 内の位置
+    fn selected_playlist_index(&self) -> Option<usize> {
+        self.filtered_playlists
+            .get(self.playlist_state.selected()?)
+            .copied()
+    }
+
+    pub fn selected_playlist(&self) -> Option<&Playlist> {
+        self.playlists.get(self.selected_playlist_index()?)
+    }
+
+    /// メディアキーなどから届いた操作を実行する
+    #[cfg(all(feature = "mpris", target_os = "linux"))]
+    async fn process_mpris(&mut self) {
+        let Some(command) = self.mpris.as_mut().and_then(Mpris::try_recv) else {
+            return;
+        };
+
+        // キーと同じ操作はキーと同じ経路で（Premium と広告の確認も同じになる）
+        let action = match command {
+            MprisCommand::PlayPause => Some(Action::TogglePlayback),
+            MprisCommand::Next => Some(Action::Next),
+            MprisCommand::Previous => Some(Action::Previous),
+            _ => None,
+        };
+        if let Some(action) = action {
+            self.apply_now_playing_action(action).await;
+            return;
+        }
+
+        // 残りは対応するキーが無いので、同じ条件をここで見る
+        if !self.is_premium() {
+            self.set_status(StatusKind::Error, "Premium required".to_string());
+            return;
+        }
+        match command {
+            MprisCommand::Play => {
+                let result = self.spotify_client.resume().await;
+                self.report(result, None);
+            }
+            MprisCommand::Pause => {
+                let result = self.spotify_client.pause().await;
+                self.report(result, None);
+            }
+            // 広告の間はシークしない（キーの 0〜9 と同じ）
+            MprisCommand::Seek { .. } | MprisCommand::SetPosition { .. } if self.is_ad() => return,
+            MprisCommand::Seek { offset_ms } => {
+                let position_ms = self.interpolated_progress_ms() + offset_ms;
+                self.seek_to(position_ms.max(0)).await;
+            }
+            MprisCommand::SetPosition { position_ms } => self.seek_to(position_ms).await,
+            MprisCommand::PlayPause | MprisCommand::Next | MprisCommand::Previous => {}
+        }
+        self.force_refresh();
+    }
+
+    /// 曲か再生状態が変わっていればファイルとソケットに書き出す（失敗しても再生は続ける）
+    fn write_now_playing(&mut self) {
+        if let Some(file) = self.now_playing_file.as_mut() {
+            let _ = file.update(&self.spotify_client.spotify_player);
+        }
+        if let Some(socket) = &self.state_socket {
+            socket.update(&self.spotify_client.spotify_player);
+        }
+    }
+
+    /// エピソードの再生位置を記録し、別のエピソードに移ったら書き出す
+    fn update_resume_points(&mut self, player: &SpotifyPlayer) {
+        if !self.config.resume_episodes {
+            return;
+        }
+
+        let previous_id = episode_id(&self.spotify_client.spotify_player);
+        let current_id = episode_id(player);
+        if previous_id != current_id {
+            if previous_id.is_some() {
+                let _ = self.resume_points.save();
+            }
+            self.resume_offer = self.resume_offer_for(player);
+        }
+
+        // 再開を提案している間は Spotify 側の古い位置で上書きしない
+        if let Some((_, saved_ms)) = &self.resume_offer {
+            if player.progress_ms.unwrap_or(0) < *saved_ms {
+                return;
+            }
+            // 保存位置を過ぎたら提案を取り下げる
+            self.resume_offer = None;
+        }
+        if let (Some(id), Some(track), Some(progress_ms)) =
+            (current_id, player.item.as_ref(), player.progress_ms)
+        {
+            self.resume_points.update(id, progress_ms, track.duration_ms);
+        }
+    }
+
+    fn resume_offer_for(&self, player: &SpotifyPlayer) -> Option<(String, i64)> {
+        if !self.config.resume_episodes {
+            return None;
+        }
+        let id = episode_id(player)?;
+        let saved_ms = self.resume_points.get(id)?;
+        let progress_ms = player.progress_ms.unwrap_or(0);
+        (saved_ms > progress_ms + RESUME_THRESHOLD_MS).then(|| (id.to_string(), saved_ms))
+    }
+
+    /// ポーリングの間は経過時間で再生位置を補間する
+    fn interpolated_progress_ms(&self) -> i64 {
+        let player = &self.spotify_client.spotify_player;
+        let progress_ms = player.progress_ms.unwrap_or(0);
+        if !player.is_playing {
+            return progress_ms;
+        }
+
+        let elapsed_ms = self.last_progress_update.elapsed().as_millis() as i64;
+        let duration_ms = player.item.as_ref().map_or(0, |track| track.duration_ms);
+        (progress_ms + elapsed_ms).min(duration_ms)
+    }
+
     fn draw(&mut self, frame: &mut Frame) {
+        // マーキーは描画ごとに1列進める
+        self.marquee_tick = self.marquee_tick.wrapping_add(1);
+        if self.playlists_task.is_some() || self.search.is_searching() {
+            self.spinner_frame = (self.spinner_frame + 1) % SPINNER_FRAMES.len();
+        }
         frame.render_widget(self, frame.area());
     }
 
     async fn handle_events(&mut self) -> io::Result<()> {
-        // 100ms のタイムアウトで非ブロッキングチェック
-        if event::poll(Duration::from_millis(100))? {
+        // 100ms のタイムアウトで非ブロッキングチェック（検索の待ち時間が先に切れるならそこまで）
+        let timeout = self
+            .search
+            .time_until_due()
+            .map_or(EVENT_POLL_TIMEOUT, |due| due.min(EVENT_POLL_TIMEOUT));
+        if event::poll(timeout)? {
             match event::read()? {
                 Event::Key(key_event) if key_event.kind == KeyEventKind::Press => {
                     self.handle_key_event(key_event).await
                 }
+                Event::Mouse(mouse_event) => self.handle_mouse_event(mouse_event).await,
+                Event::FocusLost => self.focused = false,
+                Event::FocusGained => {
+                    // 戻ってきたらすぐに最新の状態を表示する
+                    self.focused = true;
+                    self.force_refresh();
+                }
                 _ => {}
             }
         }
@@ -114,113 +842,2390 @@ impl App {
     }
 
     async fn handle_key_event(&mut self, key_event: KeyEvent) {
+        // raw モードでは Ctrl-C がシグナルにならずキー入力として届く
+        if key_event.code == KeyCode::Char('c') && key_event.modifiers.contains(KeyModifiers::CONTROL) {
+            return self.exit();
+        }
+
+        // 時計の画面とモーダルはどのキーでも閉じるだけ
+        if std::mem::take(&mut self.sleep_clock) || self.overlay.take().is_some() {
+            return;
+        }
+        if self.playlist_picker.is_some() {
+            return self.handle_playlist_picker_key(key_event).await;
+        }
+        if self.new_playlist_name.is_some() {
+            return self.handle_new_playlist_key(key_event).await;
+        }
+        if self.sleep_minutes.is_some() {
+            return self.handle_sleep_minutes_key(key_event);
+        }
+        if self.current_page == Page::PlaylistTracks
+            && self.playlist_view.as_ref().is_some_and(|view| view.confirm_remove)
+        {
+            return self.handle_confirm_remove_key(key_event).await;
+        }
+
+        // 検索ページでは文字をすべて入力として扱う（Ctrl 付きは操作）
+        if self.current_page == Page::Search && !key_event.modifiers.contains(KeyModifiers::CONTROL) {
+            match key_event.code {
+                KeyCode::Char(c) => return self.search.push(c),
+                KeyCode::Backspace => return self.search.pop(),
+                _ => {}
+            }
+        }
+
+        // 絞り込みの入力中は文字をすべて入力として扱う
+        if self.current_page == Page::PlaylistList
+            && let Some(query) = self.playlist_filter.as_mut()
+        {
+            match key_event.code {
+                KeyCode::Char(c) => query.push(c),
+                KeyCode::Backspace => {
+                    query.pop();
+                }
+                KeyCode::Esc => self.playlist_filter = None,
+                _ => return self.apply_key(key_event).await,
+            }
+            return self.refilter_playlists();
+        }
+
+        self.apply_key(key_event).await;
+    }
+
+    /// キーを表示中のページの操作に変換して実行する（割り当ての無いキーは無視）
+    async fn apply_key(&mut self, key_event: KeyEvent) {
+        // 数字は次の移動の回数として貯める（再生画面の 0-9 はシークなので除く、先頭の 0 は無視）
+        if self.current_page != Page::NowPlaying
+            && let KeyCode::Char(c @ '0'..='9') = key_event.code
+            && (c != '0' || self.pending_count.is_some())
+        {
+            let digit = c as usize - '0' as usize;
+            let count = self.pending_count.unwrap_or(0).saturating_mul(10).saturating_add(digit);
+            self.pending_count = Some(count);
+            return;
+        }
+
+        if let Some(action) = Action::from_key(self.current_page, key_event) {
+            self.apply_action(action).await;
+        }
+        // 回数は直後のキー 1 回分だけ（移動以外のキーでも捨てる）
+        self.pending_count = None;
+    }
+
+    /// 操作を表示中のページに対して実行する（キー入力以外からも同じ操作を送れる）
+    pub async fn apply_action(&mut self, action: Action) {
+        // どのページでも有効な操作
+        match action {
+            Action::Quit => return self.exit(),
+            Action::ShowHelp => {
+                self.overlay = Some(Overlay::Help);
+                return;
+            }
+            Action::HistoryBack => return self.go_back(),
+            Action::HistoryForward => return self.go_forward(),
+            Action::Refresh => return self.refresh_page().await,
+            Action::OpenNowPlaying => {
+                // 離れている間に変わっているかもしれないので待たずに取り直す
+                self.force_refresh();
+                return self.navigate_to(Page::NowPlaying);
+            }
+            _ => {}
+        }
+
         match self.current_page {
-            Page::PlaylistList => self.handle_playlist_list_key(key_event).await,
-            Page::NowPlaying => self.handle_now_playing_key(key_event).await,
+            Page::PlaylistList => self.apply_playlist_list_action(action).await,
+            Page::PlaylistTracks => self.apply_playlist_tracks_action(action).await,
+            Page::NowPlaying => self.apply_now_playing_action(action).await,
+            Page::Devices => self.apply_devices_action(action).await,
+            Page::QueuePreview => self.apply_queue_preview_action(action),
+            Page::NewReleases => self.apply_new_releases_action(action).await,
+            Page::AlbumTracks => self.apply_album_tracks_action(action).await,
+            Page::Search => self.apply_search_action(action).await,
         }
     }
 
-    async fn handle_playlist_list_key(&mut self, key_event: KeyEvent) {
-        match key_event.code {
-            KeyCode::Char('q') => self.exit(),
-            KeyCode::Up | KeyCode::Char('k') => {
-                if let Some(selected) = self.playlist_state.selected() {
-                    if selected > 0 {
-                        self.playlist_state.select(Some(selected - 1));
-                    }
-                }
+    async fn handle_mouse_event(&mut self, mouse_event: MouseEvent) {
+        if self.overlay.is_some()
+            || self.playlist_picker.is_some()
+            || self.new_playlist_name.is_some()
+            || self.sleep_minutes.is_some()
+            || self.sleep_clock
+        {
+            return;
+        }
+        let (column, row) = (mouse_event.column, mouse_event.row);
+
+        match (self.current_page, mouse_event.kind) {
+            // ホイールは端で回り込まずに止める
+            (Page::PlaylistList, MouseEventKind::ScrollUp | MouseEventKind::ScrollDown) => {
+                let movement = if mouse_event.kind == MouseEventKind::ScrollUp {
+                    Movement::PageUp(1)
+                } else {
+                    Movement::PageDown(1)
+                };
+                let selected = self.playlist_state.selected();
+                self.playlist_state
+                    .select(move_selection(selected, self.filtered_playlists.len(), movement));
             }
-            KeyCode::Down | KeyCode::Char('j') => {
-                if let Some(selected) = self.playlist_state.selected() {
-                    if selected < self.playlists.len() - 1 {
-                        self.playlist_state.select(Some(selected + 1));
-                    }
+            (Page::PlaylistList, MouseEventKind::Down(MouseButton::Left)) => {
+                let area = self.playlist_list_area;
+                if !area.contains((column, row).into()) {
+                    return;
+                }
+                let index = self.playlist_state.offset() + ((row - area.y) / self.playlist_row_height) as usize;
+                if index >= self.filtered_playlists.len() {
+                    return;
+                }
+                // 選択中の行をもう一度クリック（ダブルクリック）したら再生
+                if self.playlist_state.selected() == Some(index) {
+                    self.play_selected_playlist().await;
+                } else {
+                    self.playlist_state.select(Some(index));
                 }
             }
-            KeyCode::Enter => {
-                if let Some(selected) = self.playlist_state.selected() {
-                    if let Some(playlist) = self.playlists.get(selected) {
-                        // プレイリストを再生
-                        let _ = self.spotify_client.play_playlist(&playlist.id).await;
-                        // 再生画面に遷移
-                        self.current_page = Page::NowPlaying;
-                    }
+            (Page::NowPlaying, MouseEventKind::Down(MouseButton::Left)) => {
+                let area = self.progress_area;
+                let Some(track) = &self.spotify_client.spotify_player.item else {
+                    return;
+                };
+                if area.width == 0 || !area.contains((column, row).into()) {
+                    return;
                 }
+                if !self.is_premium() {
+                    return self.set_status(StatusKind::Error, "Premium required".to_string());
+                }
+                // クリックした位置の割合でシーク
+                let ratio = (column - area.x) as f64 / area.width as f64;
+                let position_ms = (track.duration_ms as f64 * ratio) as i64;
+                self.seek_to(position_ms).await;
             }
             _ => {}
         }
     }
 
-    async fn handle_now_playing_key(&mut self, key_event: KeyEvent) {
-        match key_event.code {
-            KeyCode::Char('q') => self.exit(),
-            KeyCode::Esc | KeyCode::Char('p') => {
-                // プレイリスト一覧に戻る
-                self.current_page = Page::PlaylistList;
+    async fn play_selected_playlist(&mut self) {
+        let Some(playlist) = self.selected_playlist().cloned() else {
+            return;
+        };
+        let result = match self.config.playlist_play_mode {
+            PlayMode::InOrder => self.spotify_client.play_playlist(&playlist.id, None, None).await,
+            PlayMode::Shuffle => self.shuffle_play_playlist(&playlist).await,
+        };
+        let message = format!("Playing {}", playlist.name);
+        self.report(result, Some(message));
+        self.force_refresh();
+        // 再生画面に遷移
+        self.navigate_to(Page::NowPlaying);
+    }
+
+    /// ランダムな曲から再生してシャッフルを有効にする
+    ///
+    /// 再生中のデバイスが無いとシャッフルの切り替えは 404 になるので、先に再生を始めてから切り替える。
+    async fn shuffle_play_playlist(&mut self, playlist: &Playlist) -> Result<(), Box<dyn std::error::Error>> {
+        let total = playlist.tracks.total.max(0) as usize;
+        let position = if total > 0 { rand::thread_rng().gen_range(0..total) } else { 0 };
+        let context_uri = format!("spotify:playlist:{}", playlist.id);
+        self.spotify_client
+            .play_context(&context_uri, Some(PlayOffset::Position(position)), 0, None)
+            .await?;
+        // Free プランでは切り替えられないので、ランダムな曲から順に流すだけにする
+        if !self.is_premium() {
+            return Ok(());
+        }
+        self.spotify_client.set_shuffle(true).await
+    }
+
+    /// リストの移動なら Movement に変換する（ページ送りは表示できる行数ずつ）
+    fn list_movement(&self, action: Action) -> Option<Movement> {
+        let movement = match (action, self.pending_count) {
+            // 回数付きは端で回り込まずに止める
+            (Action::MoveUp, Some(count)) => Movement::PageUp(count),
+            (Action::MoveDown, Some(count)) => Movement::PageDown(count),
+            _ => return self.single_movement(action),
+        };
+        Some(movement)
+    }
+
+    fn single_movement(&self, action: Action) -> Option<Movement> {
+        let movement = match action {
+            Action::MoveUp => Movement::Previous,
+            Action::MoveDown => Movement::Next,
+            Action::PageUp => Movement::PageUp(self.list_page_size),
+            Action::PageDown => Movement::PageDown(self.list_page_size),
+            Action::First => Movement::First,
+            Action::Last => Movement::Last,
+            _ => return None,
+        };
+        Some(movement)
+    }
+
+    async fn apply_playlist_list_action(&mut self, action: Action) {
+        if let Some(movement) = self.list_movement(action) {
+            return self.move_playlist_selection(movement);
+        }
+        match action {
+            Action::RetryPlaylists if self.playlists_error.is_some() => self.load_playlists(),
+            Action::Filter => {
+                self.playlist_filter = Some(String::new());
+                self.refilter_playlists();
             }
-            KeyCode::Left => {
-                let _ = self
-                    .spotify_client
-                    .skip_track(SkipDirection::Previous)
-                    .await;
+            Action::Select => self.play_selected_playlist().await,
+            Action::Open => {
+                if let Some(index) = self.selected_playlist_index()
+                    && let Some(view) = self.fetch_playlist_view(index).await
+                {
+                    self.playlist_view = Some(view);
+                    self.navigate_to(Page::PlaylistTracks);
+                }
             }
-            KeyCode::Right => {
-                let _ = self.spotify_client.skip_track(SkipDirection::Next).await;
+            Action::Enqueue => {
+                if let Some(index) = self.selected_playlist_index() {
+                    self.open_queue_preview(index).await;
+                }
             }
+            Action::OpenNewReleases => self.open_new_releases().await,
+            Action::OpenSearch => self.navigate_to(Page::Search),
+            Action::CreatePlaylist => self.new_playlist_name = Some(String::new()),
             _ => {}
         }
     }
 
-    fn exit(&mut self) {
-        self.exit = true;
+    async fn apply_search_action(&mut self, action: Action) {
+        if let Some(movement) = self.list_movement(action) {
+            return self.search.move_selection(movement);
+        }
+        match action {
+            Action::Back => self.go_back(),
+            Action::Select => {
+                let Some(track) = self.search.selected_track() else {
+                    return;
+                };
+                let message = format!("Playing {}", track.name);
+                let result = self.spotify_client.play_track(&track.uri, 0, None).await;
+                if self.config.auto_advance && result.is_ok() {
+                    // 選んだ曲の後ろの検索結果を続けて流す
+                    let selected = self.search.state.selected().unwrap_or(0);
+                    let upcoming = self.search.results.iter().skip(selected + 1).cloned();
+                    self.continue_queue.start(track.uri.clone(), upcoming);
+                }
+                self.report(result, Some(message));
+                self.force_refresh();
+                self.navigate_to(Page::NowPlaying);
+            }
+            // 今の曲はそのままで次に流す
+            Action::Enqueue => {
+                let Some(track) = self.search.selected_track() else {
+                    return;
+                };
+                let message = format!("Queued {}", track.name);
+                let result = self.spotify_client.add_to_queue(&track.uri).await;
+                self.report(result, Some(message));
+            }
+            _ => {}
+        }
     }
-}
 
-// ANCHOR: impl Widget
-impl Widget for &mut App {
-    fn render(self, area: Rect, buf: &mut Buffer) {
-        // 背景を黒でクリア
-        let background = Block::default().style(Style::default().bg(Color::Black));
-        background.render(area, buf);
+    async fn open_new_releases(&mut self) {
+        if self.load_new_releases().await {
+            self.navigate_to(Page::NewReleases);
+        }
+    }
 
-        match self.current_page {
-            Page::PlaylistList => self.render_playlist_list(area, buf),
-            Page::NowPlaying => self.render_now_playing(area, buf),
+    /// 新着アルバムを取り直す（失敗したらステータス行に出して false）
+    async fn load_new_releases(&mut self) -> bool {
+        match self.spotify_client.get_new_releases().await {
+            Ok(albums) => {
+                let selected = self.new_releases_state.selected().unwrap_or(0);
+                self.new_releases_state
+                    .select((!albums.is_empty()).then(|| selected.min(albums.len() - 1)));
+                self.new_releases = albums;
+                true
+            }
+            Err(e) => {
+                self.set_status(StatusKind::Error, e.to_string());
+                false
+            }
         }
     }
-}
 
-impl App {
-    fn render_playlist_list(&mut self, area: Rect, buf: &mut Buffer) {
-        let custom_green = Color::Rgb(0x0A, 0xE1, 0x64);
+    /// アルバムの曲と保存状態を取得して曲一覧を作る
+    async fn fetch_album_view(&mut self, album: Album) -> Option<AlbumView> {
+        let id = album.id.clone()?;
+        let mut view = match self.spotify_client.get_album_tracks(&id).await {
+            Ok(tracks) => AlbumView::new(album, tracks),
+            Err(e) => {
+                self.set_status(StatusKind::Error, e.to_string());
+                return None;
+            }
+        };
+        match self.spotify_client.is_album_saved(&id).await {
+            Ok(saved) => view.saved = Some(saved),
+            Err(e) => tracing::debug!(error = %e, "Failed to check saved album"),
+        }
+        Some(view)
+    }
 
-        let layout = Layout::default()
-            .direction(Direction::Vertical)
-            .constraints([
-                Constraint::Length(3), // ヘッダー
-                Constraint::Min(0),    // プレイリストリスト
-                Constraint::Length(2), // フッター
-            ])
-            .split(area);
+    /// 開いているアルバムの保存を切り替える
+    async fn toggle_album_saved(&mut self) {
+        let Some(view) = self.album_view.as_mut() else {
+            return;
+        };
+        let (Some(id), Some(saved)) = (view.album.id.as_deref(), view.saved) else {
+            return;
+        };
+        let result = self.spotify_client.set_album_saved(id, !saved).await;
+        if result.is_ok() {
+            view.saved = Some(!saved);
+        }
+        let message = if saved { "Removed from your library" } else { "Saved to your library" };
+        self.report(result, Some(message.to_string()));
+    }
 
-        // ヘッダー
-        let title = Line::from(" Your Playlists ".bold().fg(custom_green));
-        let header_block = Block::bordered()
-            .border_set(border::ROUNDED)
-            .border_style(Style::default().fg(custom_green));
-        let header = Paragraph::new(title)
+    async fn apply_new_releases_action(&mut self, action: Action) {
+        if let Some(movement) = self.list_movement(action) {
+            let selected = move_selection(self.new_releases_state.selected(), self.new_releases.len(), movement);
+            return self.new_releases_state.select(selected);
+        }
+        match action {
+            Action::Back => self.go_back(),
+            Action::Open => {
+                let Some(album) = self.new_releases_state.selected().and_then(|i| self.new_releases.get(i)) else {
+                    return;
+                };
+                if let Some(view) = self.fetch_album_view(album.clone()).await {
+                    self.album_view = Some(view);
+                    self.navigate_to(Page::AlbumTracks);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    async fn apply_album_tracks_action(&mut self, action: Action) {
+        let movement = self.list_movement(action);
+        let Some(view) = self.album_view.as_mut() else {
+            return;
+        };
+        if let Some(movement) = movement {
+            return view.move_selection(movement);
+        }
+        match action {
+            Action::Back => self.go_back(),
+            Action::Select => {
+                // 選んだ曲からアルバムを再生
+                let (Some(album_id), Some(track)) = (view.album.id.as_deref(), view.selected_track()) else {
+                    return;
+                };
+                let message = format!("Playing {}", track.name);
+                let result = self
+                    .spotify_client
+                    .play_album(album_id, Some(&track.uri), None)
+                    .await;
+                self.report(result, Some(message));
+                self.force_refresh();
+                self.navigate_to(Page::NowPlaying);
+            }
+            Action::ToggleSave => self.toggle_album_saved().await,
+            _ => {}
+        }
+    }
+
+    fn move_playlist_selection(&mut self, movement: Movement) {
+        // 読み込み前や絞り込みで 0 件のときは選択なし
+        if self.filtered_playlists.is_empty() {
+            self.playlist_state.select(None);
+            return;
+        }
+        let selected = move_selection(self.playlist_state.selected(), self.filtered_playlists.len(), movement);
+        self.playlist_state.select(selected);
+    }
+
+    /// プレイリストの曲を取得して (曲, 一覧に出ていた件数) を返す（失敗はステータス行に出す）
+    async fn fetch_playlist_tracks(&mut self, index: usize) -> Option<(Vec<Track>, usize)> {
+        let playlist = &self.playlists[index];
+        match self.spotify_client.get_playlist_tracks(&playlist.id).await {
+            Ok(tracks) => {
+                // 一覧の件数は古かったり再生できない曲を含んだりするので、実際に取れた数で置き換える
+                let reported = playlist.tracks.total.max(0) as usize;
+                self.playlists[index].tracks.total = tracks.len() as i32;
+                Some((tracks, reported))
+            }
+            Err(e) => {
+                self.set_status(StatusKind::Error, e.to_string());
+                None
+            }
+        }
+    }
+
+    /// プレイリストの曲とフォロー状態を取得して曲一覧を作る
+    async fn fetch_playlist_view(&mut self, index: usize) -> Option<PlaylistView> {
+        let (tracks, reported) = self.fetch_playlist_tracks(index).await?;
+        // 1 件ずつ取り直すと一覧より新しい件数やフォロワー数が分かる（失敗したら一覧の内容で開く）
+        let (playlist, reported) = match self.spotify_client.get_playlist(&self.playlists[index].id).await {
+            Ok(playlist) => {
+                let total = playlist.tracks.total.max(0) as usize;
+                (playlist, total)
+            }
+            Err(e) => {
+                tracing::debug!(error = %e, "Failed to fetch playlist");
+                (self.playlists[index].clone(), reported)
+            }
+        };
+        let mut view = PlaylistView::new(playlist, tracks, reported);
+        // 確認できなくても曲一覧は開く（ボタンを出さないだけ）
+        match self.spotify_client.is_following_playlist(&view.playlist.id).await {
+            Ok(following) => view.following = Some(following),
+            Err(e) => tracing::debug!(error = %e, "Failed to check playlist follow state"),
+        }
+        Some(view)
+    }
+
+    /// 開いているプレイリストのフォローを切り替える
+    async fn toggle_playlist_follow(&mut self) {
+        let Some(view) = self.playlist_view.as_mut() else {
+            return;
+        };
+        let Some(following) = view.following else {
+            return;
+        };
+        // 自分のプレイリストのフォロー解除は削除と同じなのでここではさせない
+        let user_id = self.spotify_client.current_user.as_ref().map(|user| user.id.as_str());
+        let owned = view.playlist.owner.as_ref().is_some_and(|owner| Some(owner.id.as_str()) == user_id);
+        if following && owned {
+            return self.set_status(StatusKind::Error, "Can't unfollow your own playlist".to_string());
+        }
+
+        let id = view.playlist.id.clone();
+        let result = self.spotify_client.set_playlist_following(&id, !following).await;
+        if result.is_ok() {
+            view.following = Some(!following);
+            // 一覧にも反映する（選択中のプレイリストはそのまま）
+            self.restore_playlist_id = self.selected_playlist().map(|playlist| playlist.id.clone());
+            self.load_playlists();
+        }
+        let message = if following { "Unfollowed playlist" } else { "Following playlist" };
+        self.report(result, Some(message.to_string()));
+    }
+
+    /// 開いているプレイリストを編集できるか（できなければステータス行に出す）
+    async fn can_edit_open_playlist(&mut self) -> bool {
+        let Some(user_id) = self.current_user_id().await else {
+            return false;
+        };
+        let editable = self
+            .playlist_view
+            .as_ref()
+            .is_some_and(|view| view.playlist.is_editable_by(&user_id));
+        if !editable {
+            self.set_status(StatusKind::Error, "You can't edit this playlist".to_string());
+        }
+        editable
+    }
+
+    /// プレイリスト名の入力（Enter で作成、Esc で取りやめ）
+    async fn handle_new_playlist_key(&mut self, key_event: KeyEvent) {
+        let Some(name) = self.new_playlist_name.as_mut() else {
+            return;
+        };
+        match key_event.code {
+            KeyCode::Char(c) => name.push(c),
+            KeyCode::Backspace => {
+                name.pop();
+            }
+            KeyCode::Esc => self.new_playlist_name = None,
+            KeyCode::Enter if !name.trim().is_empty() => {
+                let name = name.trim().to_string();
+                self.new_playlist_name = None;
+                self.create_playlist(&name).await;
+            }
+            _ => {}
+        }
+    }
+
+    fn handle_sleep_minutes_key(&mut self, key_event: KeyEvent) {
+        let Some(minutes) = self.sleep_minutes.as_mut() else {
+            return;
+        };
+        match key_event.code {
+            // 999 分（16 時間半）まで
+            KeyCode::Char(c @ '0'..='9') if minutes.len() < 3 => minutes.push(c),
+            KeyCode::Backspace => {
+                minutes.pop();
+            }
+            KeyCode::Esc => self.sleep_minutes = None,
+            KeyCode::Enter => {
+                let Some(minutes) = minutes.parse::<u64>().ok().filter(|&minutes| minutes > 0) else {
+                    return;
+                };
+                self.sleep_minutes = None;
+                self.sleep_at = Some(Instant::now() + Duration::from_secs(minutes * 60));
+                self.set_status(StatusKind::Success, format!("Pausing in {} min", minutes));
+            }
+            _ => {}
+        }
+    }
+
+    /// スリープタイマーの時刻になったら一時停止する（最後の 1 分は音量を下げていく）
+    async fn check_sleep_timer(&mut self) {
+        let Some(sleep_at) = self.sleep_at else {
+            return;
+        };
+        let remaining = sleep_at.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            return self.fire_sleep_timer().await;
+        }
+        if self.config.sleep_fade && remaining < SLEEP_FADE {
+            self.fade_out(remaining).await;
+        }
+    }
+
+    /// 残り時間に比例するところまで音量を下げる
+    async fn fade_out(&mut self, remaining: Duration) {
+        let player = &self.spotify_client.spotify_player;
+        let Some(device) = player.device.as_ref().filter(|device| device.supports_volume) else {
+            return;
+        };
+        if !player.is_playing {
+            return;
+        }
+        let current = device.volume_percent.unwrap_or(0);
+        let start = *self.sleep_volume.get_or_insert(current);
+        let target = (start as u128 * remaining.as_millis() / SLEEP_FADE.as_millis()) as u8;
+        if current.saturating_sub(target) < SLEEP_FADE_STEP {
+            return;
+        }
+        if let Err(e) = self.spotify_client.set_volume(target).await {
+            tracing::debug!(error = %e, "Failed to fade out");
+        }
+    }
+
+    async fn fire_sleep_timer(&mut self) {
+        self.sleep_at = None;
+        let result = self.spotify_client.pause().await;
+        self.restore_sleep_volume().await;
+        self.report(result, Some("Sleep timer paused playback".to_string()));
+        self.force_refresh();
+        match self.config.sleep_action {
+            SleepAction::Pause => {}
+            // ループを抜けた後の main で端末を元に戻す
+            SleepAction::Quit => self.exit(),
+            SleepAction::Clock => self.sleep_clock = true,
+        }
+    }
+
+    async fn cancel_sleep_timer(&mut self) {
+        self.sleep_at = None;
+        self.restore_sleep_volume().await;
+    }
+
+    /// フェードで下げた音量を、次に再生したときのために戻す
+    async fn restore_sleep_volume(&mut self) {
+        let Some(volume) = self.sleep_volume.take() else {
+            return;
+        };
+        if let Err(e) = self.spotify_client.set_volume(volume).await {
+            tracing::debug!(error = %e, "Failed to restore volume after the sleep timer");
+        }
+    }
+
+    /// 非公開のプレイリストを作って一覧の先頭に加え、選んだ状態にする
+    async fn create_playlist(&mut self, name: &str) {
+        let Some(user_id) = self.current_user_id().await else {
+            return;
+        };
+        match self.spotify_client.create_playlist(&user_id, name, false).await {
+            Ok(playlist) => {
+                // Spotify の一覧と同じく新しいものを先頭に（絞り込みは解除して見えるようにする）
+                self.playlists.insert(0, playlist);
+                self.playlist_filter = None;
+                self.refilter_playlists();
+                self.playlist_state.select(Some(0));
+                self.set_status(StatusKind::Success, format!("Created {}", name));
+            }
+            Err(e) => self.set_status(StatusKind::Error, e.to_string()),
+        }
+    }
+
+    /// 選択中の曲を削除する前に確認する（編集できるプレイリストだけ）
+    async fn confirm_remove_track(&mut self) {
+        if !self.can_edit_open_playlist().await {
+            return;
+        }
+        if let Some(view) = self.playlist_view.as_mut()
+            && view.selected_track().is_some()
+        {
+            view.confirm_remove = true;
+        }
+    }
+
+    /// 選択中の曲を 1 つ上か下へ移す（先に一覧を入れ替え、失敗したら戻す）
+    async fn move_playlist_track(&mut self, up: bool) {
+        if !self.can_edit_open_playlist().await {
+            return;
+        }
+        let Some(view) = self.playlist_view.as_mut() else {
+            return;
+        };
+        // 再生できない曲は一覧から除いているので、位置が Spotify 側とずれる
+        if view.unavailable > 0 {
+            return self.set_status(
+                StatusKind::Error,
+                "Can't reorder a playlist with unavailable tracks".to_string(),
+            );
+        }
+        let Some(from) = view.state.selected() else {
+            return;
+        };
+        let to = if up { from.checked_sub(1) } else { Some(from + 1).filter(|&to| to < view.tracks.len()) };
+        let Some(to) = to else {
+            return;
+        };
+        // insert_before は移動前の位置で数えるので、下へ移すときは 2 つ先の曲の前
+        let insert_before = if up { to } else { to + 1 };
+
+        view.swap(from, to);
+        let result = self
+            .spotify_client
+            .reorder_playlist_track(&view.playlist.id, from, insert_before, view.playlist.snapshot_id.as_deref())
+            .await;
+        match result {
+            Ok(snapshot_id) => {
+                view.playlist.snapshot_id = Some(snapshot_id);
+                let message = format!("Moved to #{}", to + 1);
+                self.set_status(StatusKind::Success, message);
+            }
+            Err(e) => {
+                view.swap(to, from);
+                self.set_status(StatusKind::Error, e.to_string());
+            }
+        }
+    }
+
+    async fn handle_confirm_remove_key(&mut self, key_event: KeyEvent) {
+        let Some(view) = self.playlist_view.as_mut() else {
+            return;
+        };
+        match key_event.code {
+            KeyCode::Char('y') | KeyCode::Enter => {
+                view.confirm_remove = false;
+                self.remove_selected_track().await;
+            }
+            KeyCode::Char('n') | KeyCode::Esc => view.confirm_remove = false,
+            _ => {}
+        }
+    }
+
+    /// 選択中の曲をプレイリストから削除して一覧にも反映する
+    async fn remove_selected_track(&mut self) {
+        let Some(view) = self.playlist_view.as_mut() else {
+            return;
+        };
+        let Some(track) = view.selected_track() else {
+            return;
+        };
+        let (uri, name) = (track.uri.clone(), track.name.clone());
+        let id = view.playlist.id.clone();
+        let result = self
+            .spotify_client
+            .remove_track_from_playlist(&id, &uri, view.playlist.snapshot_id.as_deref())
+            .await;
+        match result {
+            Ok(snapshot_id) => {
+                view.playlist.snapshot_id = Some(snapshot_id);
+                view.remove_uri(&uri);
+                let total = view.tracks.len() as i32;
+                if let Some(playlist) = self.playlists.iter_mut().find(|playlist| playlist.id == id) {
+                    playlist.tracks.total = total;
+                }
+                if let Some(uris) = self.playlist_uris.get_mut(&id) {
+                    uris.remove(&uri);
+                }
+                self.set_status(StatusKind::Success, format!("Removed {}", name));
+            }
+            Err(e) => self.set_status(StatusKind::Error, e.to_string()),
+        }
+    }
+
+    /// キューに追加する前に順番を確認する
+    async fn open_queue_preview(&mut self, index: usize) {
+        if let Some((tracks, reported)) = self.fetch_playlist_tracks(index).await {
+            let name = self.playlists[index].name.clone();
+            self.queue_preview = Some(QueuePreview::new(name, tracks, reported));
+            self.navigate_to(Page::QueuePreview);
+        }
+    }
+
+    async fn apply_playlist_tracks_action(&mut self, action: Action) {
+        let movement = self.list_movement(action);
+        let Some(view) = self.playlist_view.as_mut() else {
+            return;
+        };
+        if let Some(movement) = movement {
+            return view.move_selection(movement);
+        }
+        match action {
+            Action::Back => self.go_back(),
+            Action::Select => {
+                // 選んだ曲からプレイリストを再生
+                let Some(track) = view.selected_track() else {
+                    return;
+                };
+                let message = format!("Playing {}", track.name);
+                let result = self
+                    .spotify_client
+                    .play_playlist(&view.playlist.id, Some(&track.uri), None)
+                    .await;
+                self.report(result, Some(message));
+                self.force_refresh();
+                self.navigate_to(Page::NowPlaying);
+            }
+            Action::Enqueue => {
+                let id = view.playlist.id.clone();
+                if let Some(index) = self.playlists.iter().position(|p| p.id == id) {
+                    self.open_queue_preview(index).await;
+                }
+            }
+            Action::ToggleFollow => self.toggle_playlist_follow().await,
+            Action::RemoveTrack => self.confirm_remove_track().await,
+            Action::MoveTrackUp => self.move_playlist_track(true).await,
+            Action::MoveTrackDown => self.move_playlist_track(false).await,
+            _ => {}
+        }
+    }
+
+    fn apply_queue_preview_action(&mut self, action: Action) {
+        let movement = self.list_movement(action);
+        let Some(preview) = self.queue_preview.as_mut() else {
+            return;
+        };
+        // 追加中は中断（と全体の終了）だけ受け付ける
+        if preview.is_enqueuing() {
+            if action == Action::Back {
+                preview.abort("Cancelled".to_string());
+            }
+            return;
+        }
+
+        if let Some(movement) = movement {
+            return preview.move_selection(movement);
+        }
+        match action {
+            Action::Back => self.go_back(),
+            Action::MoveTrackUp => preview.move_selected(true),
+            Action::MoveTrackDown => preview.move_selected(false),
+            Action::ToggleInclude => preview.toggle_selected(),
+            Action::Select => preview.confirm(),
+            _ => {}
+        }
+    }
+
+    /// 確認済みのキューを1ループに1曲ずつ追加する（進捗を描画できるように）
+    async fn process_queue_preview(&mut self) {
+        let Some(preview) = self.queue_preview.as_mut() else {
+            return;
+        };
+        let Some(uri) = preview.next_pending() else {
+            return;
+        };
+        match self.spotify_client.add_to_queue(uri).await {
+            Ok(()) => {
+                preview.mark_queued();
+                if !preview.is_enqueuing()
+                    && let Some((queued, _)) = preview.progress()
+                {
+                    self.set_status(StatusKind::Success, format!("Queued {} tracks", queued));
+                }
+            }
+            Err(e) => {
+                preview.abort(e.to_string());
+                self.set_status(StatusKind::Error, e.to_string());
+            }
+        }
+    }
+
+    async fn apply_now_playing_action(&mut self, action: Action) {
+        match action {
+            Action::Back => {
+                // 履歴がなければプレイリスト一覧に戻る
+                match self.nav_history.back(self.current_page) {
+                    Some(page) => self.current_page = page,
+                    None => self.navigate_to(Page::PlaylistList),
+                }
+            }
+            Action::OpenPlaylists => self.navigate_to(Page::PlaylistList),
+            // Free プランでは 403 になる操作（次の曲へは回数制限付きで使える）
+            Action::TogglePlayback
+            | Action::VolumeUp
+            | Action::VolumeDown
+            | Action::Resume
+            | Action::SeekTenths(_)
+            | Action::Restart
+                if !self.is_premium() =>
+            {
+                self.set_status(StatusKind::Error, "Premium required".to_string());
+            }
+            // 広告はスキップできない（API も 403 を返す）
+            Action::Previous | Action::Next if self.is_ad() => {
+                self.set_status(StatusKind::Error, "Can't skip during an ad".to_string());
+            }
+            Action::Previous => {
+                let result = self
+                    .spotify_client
+                    .skip_track(SkipDirection::Previous)
+                    .await;
+                self.report(result, None);
+                self.last_progress_update = Instant::now();
+                self.force_refresh();
+            }
+            Action::Next => {
+                let result = self.spotify_client.skip_track(SkipDirection::Next).await;
+                self.report(result, None);
+                self.last_progress_update = Instant::now();
+                self.force_refresh();
+            }
+            Action::TogglePlayback => {
+                let result = self.spotify_client.toggle_playback().await;
+                self.report(result, None);
+                self.last_progress_update = Instant::now();
+                self.force_refresh();
+            }
+            Action::CopyLink => self.copy_share_link(),
+            Action::OpenInBrowser => self.open_in_browser(),
+            Action::SleepTimer => {
+                if self.sleep_at.is_some() {
+                    self.cancel_sleep_timer().await;
+                    self.set_status(StatusKind::Success, "Sleep timer cancelled".to_string());
+                } else {
+                    self.sleep_minutes = Some(String::new());
+                }
+            }
+            Action::AddToPlaylist => self.open_playlist_picker().await,
+            // 0〜9 で曲の 0%〜90% の位置へ（mpv と同じ）
+            Action::SeekTenths(tenths) if !self.is_ad() => {
+                let Some(track) = &self.spotify_client.spotify_player.item else {
+                    return;
+                };
+                if track.duration_ms <= 0 {
+                    return;
+                }
+                let position_ms = (track.duration_ms * tenths as i64 / 10).min(track.duration_ms);
+                self.seek_to(position_ms).await;
+            }
+            // 前の曲へ（←）は再生位置によって挙動が変わるので、確実に頭から再生し直すキー
+            Action::Restart if self.spotify_client.spotify_player.item.is_some() && !self.is_ad() => {
+                self.seek_to(0).await;
+            }
+            Action::VolumeUp => self.change_volume(true).await,
+            Action::VolumeDown => self.change_volume(false).await,
+            Action::ShowArtists if self.spotify_client.spotify_player.item.is_some() => {
+                self.overlay = Some(Overlay::Artists);
+            }
+            Action::ShowTrackInfo if self.spotify_client.spotify_player.item.is_some() => {
+                self.overlay = Some(Overlay::TrackInfo);
+            }
+            Action::OpenDevices => {
+                self.load_devices().await;
+                self.navigate_to(Page::Devices);
+            }
+            Action::Resume => {
+                // 保存しておいた位置からエピソードを再開
+                if let Some((_, position_ms)) = self.resume_offer.take() {
+                    let result = self.spotify_client.seek(position_ms).await;
+                    if result.is_ok() {
+                        self.spotify_client.spotify_player.progress_ms = Some(position_ms);
+                        self.last_progress_update = Instant::now();
+                    }
+                    let message = format!("Resumed at {}", format_time(position_ms));
+                    self.report(result, Some(message));
+                } else if let Some(last) = self.last_played_offer.take() {
+                    self.resume_last_played(last).await;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// 前回終了したときの曲を、その位置から再生し直す
+    async fn resume_last_played(&mut self, last: LastPlayed) {
+        // プレイリストなどから再生していれば、その曲の後もそのまま続くようにする
+        let result = match last.context_uri.as_deref() {
+            Some(context_uri) => {
+                let offset = Some(PlayOffset::Uri(&last.uri));
+                self.spotify_client
+                    .play_context(context_uri, offset, last.progress_ms, None)
+                    .await
+            }
+            None => self.spotify_client.play_track(&last.uri, last.progress_ms, None).await,
+        };
+        let message = format!("Resumed {} at {}", last.name, format_time(last.progress_ms));
+        self.report(result, Some(message));
+        self.force_refresh();
+    }
+
+    /// デバイスの一覧を取り直す（失敗したらステータス行に出して false）
+    async fn load_devices(&mut self) -> bool {
+        match self.spotify_client.get_devices().await {
+            Ok(devices) => {
+                // 再生中のデバイスを選んだ状態にする
+                let selected = devices.iter().position(|d| d.is_active).unwrap_or(0);
+                self.device_state.select((!devices.is_empty()).then_some(selected));
+                self.devices = devices;
+                true
+            }
+            Err(e) => {
+                self.set_status(StatusKind::Error, e.to_string());
+                false
+            }
+        }
+    }
+
+    async fn apply_devices_action(&mut self, action: Action) {
+        if let Some(movement) = self.list_movement(action) {
+            let selected = move_selection(self.device_state.selected(), self.devices.len(), movement);
+            return self.device_state.select(selected);
+        }
+        match action {
+            Action::Back => self.go_back(),
+            Action::Reload => {
+                self.load_devices().await;
+            }
+            Action::Select => {
+                let Some(device) = self.device_state.selected().and_then(|i| self.devices.get(i)) else {
+                    return;
+                };
+                // 制限付きデバイスは id が無く API から操作できない
+                let Some(id) = device.id.clone() else {
+                    let message = format!("{} can't be controlled from here", device.name);
+                    self.set_status(StatusKind::Error, message);
+                    return;
+                };
+                let message = format!("Playing on {}", device.name);
+                let result = self.spotify_client.transfer_playback(&id, true).await;
+                let ok = result.is_ok();
+                self.report(result, Some(message));
+                if ok {
+                    self.force_refresh();
+                    self.navigate_to(Page::NowPlaying);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// 再生中の曲を追加できるプレイリストの一覧を開く
+    async fn open_playlist_picker(&mut self) {
+        let Some(track) = &self.spotify_client.spotify_player.item else {
+            return;
+        };
+        // ローカルファイルは追加できない
+        if track.id.is_none() {
+            return self.set_status(
+                StatusKind::Error,
+                "Local files can't be added to playlists".to_string(),
+            );
+        }
+        let (uri, track_name) = (track.uri.clone(), track.name.clone());
+        if self.playlists.is_empty() {
+            return self.set_status(StatusKind::Error, "Playlists are not loaded yet".to_string());
+        }
+
+        let Some(user_id) = self.current_user_id().await else {
+            return;
+        };
+        let editable: Vec<usize> = self
+            .playlists
+            .iter()
+            .enumerate()
+            .filter(|(_, playlist)| playlist.is_editable_by(&user_id))
+            .map(|(i, _)| i)
+            .collect();
+        if editable.is_empty() {
+            return self.set_status(StatusKind::Error, "No playlists you can edit".to_string());
+        }
+        self.playlist_picker = Some(PlaylistPicker::new(uri, track_name, editable));
+    }
+
+    /// ログイン中のユーザーの id（起動時に取れていなければここで取り直し、失敗はステータス行に出す）
+    async fn current_user_id(&mut self) -> Option<String> {
+        if let Some(user) = &self.spotify_client.current_user {
+            return Some(user.id.clone());
+        }
+        match self.spotify_client.get_current_user().await {
+            Ok(user) => Some(self.spotify_client.current_user.insert(user).id.clone()),
+            Err(e) => {
+                self.set_status(StatusKind::Error, e.to_string());
+                None
+            }
+        }
+    }
+
+    async fn handle_playlist_picker_key(&mut self, key_event: KeyEvent) {
+        let movement = action::list_action(key_event.code).and_then(|action| self.list_movement(action));
+        let Some(picker) = self.playlist_picker.as_mut() else {
+            return;
+        };
+
+        // すでに入っている曲を追加してよいかの確認中
+        if let Some(index) = picker.confirm_duplicate {
+            match key_event.code {
+                KeyCode::Char('y') | KeyCode::Enter => self.add_to_playlist(index).await,
+                KeyCode::Char('n') | KeyCode::Esc => picker.confirm_duplicate = None,
+                _ => {}
+            }
+            return;
+        }
+
+        if let Some(movement) = movement {
+            return picker.move_selection(movement);
+        }
+        match key_event.code {
+            KeyCode::Esc | KeyCode::Char('q') => self.playlist_picker = None,
+            KeyCode::Enter => {
+                let Some(index) = picker.selected() else {
+                    return;
+                };
+                let uri = picker.uri.clone();
+                if self.config.check_duplicates {
+                    match self.playlist_contains(index, &uri).await {
+                        Ok(true) => {
+                            if let Some(picker) = self.playlist_picker.as_mut() {
+                                picker.confirm_duplicate = Some(index);
+                            }
+                            return;
+                        }
+                        Ok(false) => {}
+                        Err(e) => return self.set_status(StatusKind::Error, e.to_string()),
+                    }
+                }
+                self.add_to_playlist(index).await;
+            }
+            _ => {}
+        }
+    }
+
+    /// プレイリストにすでに曲が入っているか（曲の一覧はセッション中キャッシュする）
+    async fn playlist_contains(&mut self, index: usize, uri: &str) -> Result<bool, Box<dyn std::error::Error>> {
+        let id = self.playlists[index].id.clone();
+        if !self.playlist_uris.contains_key(&id) {
+            let tracks = self.spotify_client.get_playlist_tracks(&id).await?;
+            let uris = tracks.into_iter().map(|track| track.uri).collect();
+            self.playlist_uris.insert(id.clone(), uris);
+        }
+        Ok(self.playlist_uris[&id].contains(uri))
+    }
+
+    /// 選んだプレイリストに追加してモーダルを閉じる
+    async fn add_to_playlist(&mut self, index: usize) {
+        let Some(picker) = self.playlist_picker.take() else {
+            return;
+        };
+        let playlist = &self.playlists[index];
+        let message = format!("Added {} to {}", picker.track_name, playlist.name);
+        let result = self
+            .spotify_client
+            .add_track_to_playlist(&playlist.id, &picker.uri)
+            .await;
+        if result.is_ok() {
+            let id = playlist.id.clone();
+            self.playlists[index].tracks.total += 1;
+            if let Some(uris) = self.playlist_uris.get_mut(&id) {
+                uris.insert(picker.uri);
+            }
+        }
+        self.report(result, Some(message));
+    }
+
+    /// シークして、次の取得を待たずに表示中の再生位置も合わせる
+    async fn seek_to(&mut self, position_ms: i64) {
+        let result = self.spotify_client.seek(position_ms).await;
+        if result.is_ok() {
+            self.spotify_client.spotify_player.progress_ms = Some(position_ms);
+            self.last_progress_update = Instant::now();
+        }
+        let message = format!("Seeked to {}", format_time(position_ms));
+        self.report(result, Some(message));
+    }
+
+    /// 再生中の曲の共有リンクをコピーする（クリップボードが使えなければステータス行に出す）
+    fn copy_share_link(&mut self) {
+        let Some(track) = &self.spotify_client.spotify_player.item else {
+            return;
+        };
+        let Some(url) = track.share_url() else {
+            return self.set_status(StatusKind::Error, "Local files have no share link".to_string());
+        };
+        match self.copy_to_clipboard(&url) {
+            Ok(()) => self.set_status(StatusKind::Success, "Link copied".to_string()),
+            Err(_) => self.set_status(StatusKind::Success, url),
+        }
+    }
+
+    /// 再生中の曲を Web プレイヤーで開く（開けなければ URL をステータス行に出す）
+    fn open_in_browser(&mut self) {
+        let Some(track) = &self.spotify_client.spotify_player.item else {
+            return;
+        };
+        let Some(url) = track.share_url() else {
+            return self.set_status(StatusKind::Error, "Local files can't be opened in the browser".to_string());
+        };
+        match webbrowser::open(&url) {
+            Ok(()) => self.set_status(StatusKind::Success, "Opened in browser".to_string()),
+            Err(e) => {
+                tracing::debug!(error = %e, "Failed to open browser");
+                self.set_status(StatusKind::Success, url);
+            }
+        }
+    }
+
+    #[cfg(feature = "clipboard")]
+    fn copy_to_clipboard(&mut self, text: &str) -> Result<(), arboard::Error> {
+        let clipboard = match self.clipboard.as_mut() {
+            Some(clipboard) => clipboard,
+            None => self.clipboard.insert(arboard::Clipboard::new()?),
+        };
+        clipboard.set_text(text)
+    }
+
+    #[cfg(not(feature = "clipboard"))]
+    fn copy_to_clipboard(&mut self, _text: &str) -> Result<(), ()> {
+        Err(())
+    }
+
+    fn is_ad(&self) -> bool {
+        self.spotify_client.spotify_player.currently_playing_type == PlayingType::Ad
+    }
+
+    async fn change_volume(&mut self, up: bool) {
+        let Some(device) = &self.spotify_client.spotify_player.device else {
+            return;
+        };
+        if !device.supports_volume {
+            let message = format!("{} doesn't support volume control", device.name);
+            return self.set_status(StatusKind::Error, message);
+        }
+        let current = device.volume_percent.unwrap_or(0);
+        let volume = if up {
+            current.saturating_add(VOLUME_STEP).min(100)
+        } else {
+            current.saturating_sub(VOLUME_STEP)
+        };
+        let result = self.spotify_client.set_volume(volume).await;
+        self.report(result, Some(format!("Volume {}%", volume)));
+    }
+
+    /// 公式アプリなどでの変更を反映するため、表示中のページのデータを取り直す
+    async fn refresh_page(&mut self) {
+        match self.current_page {
+            Page::NowPlaying => {
+                self.force_refresh();
+                self.set_status(StatusKind::Success, "Refreshed".to_string());
+            }
+            Page::PlaylistList => {
+                // 取り直した後も同じプレイリストを選んだままにする
+                self.restore_playlist_id = self.selected_playlist().map(|playlist| playlist.id.clone());
+                self.playlists_refreshing = true;
+                self.playlist_uris.clear();
+                self.load_playlists();
+            }
+            Page::PlaylistTracks => {
+                let Some(view) = &self.playlist_view else {
+                    return;
+                };
+                let id = view.playlist.id.clone();
+                let selected = view.state.selected();
+                let Some(index) = self.playlists.iter().position(|playlist| playlist.id == id) else {
+                    return;
+                };
+                self.playlist_uris.remove(&id);
+                if let Some(mut view) = self.fetch_playlist_view(index).await {
+                    if let Some(selected) = selected
+                        && !view.tracks.is_empty()
+                    {
+                        view.state.select(Some(selected.min(view.tracks.len().saturating_sub(1))));
+                    }
+                    self.playlist_view = Some(view);
+                    self.set_status(StatusKind::Success, "Refreshed".to_string());
+                }
+            }
+            Page::Devices => {
+                if self.load_devices().await {
+                    self.set_status(StatusKind::Success, "Refreshed".to_string());
+                }
+            }
+            Page::NewReleases => {
+                if self.load_new_releases().await {
+                    self.set_status(StatusKind::Success, "Refreshed".to_string());
+                }
+            }
+            Page::AlbumTracks => {
+                let Some(view) = &self.album_view else {
+                    return;
+                };
+                let album = view.album.clone();
+                let selected = view.state.selected();
+                if let Some(mut view) = self.fetch_album_view(album).await {
+                    if let Some(selected) = selected
+                        && !view.tracks.is_empty()
+                    {
+                        view.state.select(Some(selected.min(view.tracks.len() - 1)));
+                    }
+                    self.album_view = Some(view);
+                    self.set_status(StatusKind::Success, "Refreshed".to_string());
+                }
+            }
+            // キューの確認は手元で並べ替え中なので取り直さない。検索は入力で取り直す
+            Page::QueuePreview | Page::Search => {}
+        }
+    }
+
+    /// 操作の結果をすぐ画面に反映するため、次のループで間隔を待たずに再生状態を取り直す
+    fn force_refresh(&mut self) {
+        // 操作より前に始めた取得は古い状態を返すので捨てて取り直す
+        if let Some(task) = self.playback_task.take() {
+            task.abort();
+        }
+        self.refresh_requested = true;
+    }
+
+    fn set_status(&mut self, kind: StatusKind, text: String) {
+        self.status_message = Some(StatusMessage {
+            text,
+            kind,
+            shown_at: Instant::now(),
+        });
+    }
+
+    /// 操作の結果をステータス行に出す（成功時はメッセージがあるときだけ）
+    fn report<T>(&mut self, result: Result<T, Box<dyn std::error::Error>>, success: Option<String>) {
+        match result {
+            Ok(_) => {
+                if let Some(text) = success {
+                    self.set_status(StatusKind::Success, text);
+                }
+            }
+            Err(e) => self.set_status(StatusKind::Error, e.to_string()),
+        }
+    }
+
+    pub fn current_page(&self) -> Page {
+        self.current_page
+    }
+
+    pub fn should_exit(&self) -> bool {
+        self.exit
+    }
+
+    /// 別のページへ移動して履歴に積む
+    fn navigate_to(&mut self, page: Page) {
+        if page != self.current_page {
+            self.nav_history.push(self.current_page);
+            self.current_page = page;
+        }
+    }
+
+    fn go_back(&mut self) {
+        if let Some(page) = self.nav_history.back(self.current_page) {
+            self.current_page = page;
+        }
+    }
+
+    fn go_forward(&mut self) {
+        if let Some(page) = self.nav_history.forward(self.current_page) {
+            self.current_page = page;
+        }
+    }
+
+    fn exit(&mut self) {
+        self.exit = true;
+    }
+}
+
+/// リストに出す曲名（露骨な表現を含む曲には [E] を付ける）
+fn list_name(track: &Track) -> String {
+    if track.explicit {
+        format!("{} [E]", track.name)
+    } else {
+        track.name.clone()
+    }
+}
+
+/// 終了を求めるシグナル（Unix では SIGINT と SIGTERM、それ以外は Ctrl-C）を待つ
+async fn shutdown_signal() {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+        let Ok(mut terminate) = signal(SignalKind::terminate()) else {
+            let _ = tokio::signal::ctrl_c().await;
+            return;
+        };
+        tokio::select! {
+            _ = tokio::signal::ctrl_c() => {}
+            _ = terminate.recv() => {}
+        }
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = tokio::signal::ctrl_c().await;
+    }
+}
+
+/// ヘッダー右上に出すフォロー・保存の状態（確認できていなければ出さない）
+fn library_badge(state: Option<bool>, on: &str, off: &str, color: Color) -> Option<Line<'static>> {
+    let line = if state? {
+        Line::from(format!(" {} ", on).fg(color).bold())
+    } else {
+        Line::from(format!(" {} ", off).fg(Color::DarkGray))
+    };
+    Some(line)
+}
+
+/// Now Playing のタイトル
+fn playback_label(player: &SpotifyPlayer, is_ad: bool) -> &'static str {
+    match (player.item.is_some() || is_ad, player.is_playing) {
+        (true, true) => " Now Playing ",
+        (true, false) => " Paused ",
+        // デバイスが起動した直後などは再生中でも item がまだ無い
+        (false, true) => " Connecting… ",
+        (false, false) => " Nothing Playing ",
+    }
+}
+
+/// 再生中のアイテムがエピソードならその id
+fn episode_id(player: &SpotifyPlayer) -> Option<&str> {
+    if player.currently_playing_type != PlayingType::Episode {
+        return None;
+    }
+    player.item.as_ref()?.id.as_deref()
+}
+
+// ANCHOR: impl Widget
+impl Widget for &mut App {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        // 背景を黒でクリア
+        let background = Block::default().style(Style::default().bg(Color::Black));
+        background.render(area, buf);
+
+        // 何も収まらないほど小さい枠（分割したペインなど）や時計の画面ではクリックを受け付けない
+        if area.width < MIN_RENDER_WIDTH || area.height == 0 || self.sleep_clock {
+            self.progress_area = Rect::default();
+            self.playlist_list_area = Rect::default();
+            if self.sleep_clock {
+                render_sleep_clock(area, buf);
+            }
+            return;
+        }
+        self.resize_separator(area.width);
+
+        match self.current_page {
+            Page::PlaylistList => self.render_playlist_list(area, buf),
+            Page::PlaylistTracks => self.render_playlist_tracks(area, buf),
+            Page::NowPlaying => self.render_now_playing(area, buf),
+            Page::Devices => self.render_devices(area, buf),
+            Page::QueuePreview => self.render_queue_preview(area, buf),
+            Page::NewReleases => self.render_new_releases(area, buf),
+            Page::AlbumTracks => self.render_album_tracks(area, buf),
+            Page::Search => self.render_search(area, buf),
+        }
+
+        if self.playlist_picker.is_some() {
+            self.render_playlist_picker(area, buf);
+        }
+        if let Some(name) = &self.new_playlist_name {
+            render_name_input(" New playlist ", name, "Create", area, buf);
+        }
+        if let Some(minutes) = &self.sleep_minutes {
+            render_name_input(" Sleep timer (minutes) ", minutes, "Start", area, buf);
+        }
+
+        match self.overlay {
+            Some(Overlay::Help) => self.render_help_overlay(area, buf),
+            Some(Overlay::Artists) => self.render_artists_overlay(area, buf),
+            Some(Overlay::TrackInfo) => self.render_track_info_overlay(area, buf),
+            None => {}
+        }
+    }
+}
+
+/// 中央にモーダルを表示する（閉じるのはどのキーでも）
+fn render_popup(title: &str, lines: Vec<Line>, area: Rect, buf: &mut Buffer) {
+    render_dialog(title, lines, Line::from(" press any key ".fg(Color::DarkGray)), area, buf);
+}
+
+/// 中央にモーダルを表示する（下の枠に操作の案内）
+fn render_dialog(title: &str, lines: Vec<Line>, hint: Line, area: Rect, buf: &mut Buffer) {
+    let custom_green = theme::accent();
+
+    let content_width = lines.iter().map(Line::width).max().unwrap_or(0) as u16;
+    let title_width = title.width() as u16;
+    let popup = centered_rect(content_width.max(title_width) + 4, lines.len() as u16 + 2, area);
+
+    let block = Block::bordered()
+        .border_set(border::ROUNDED)
+        .border_style(Style::default().fg(custom_green))
+        .title(Line::from(title.bold().fg(custom_green)).centered())
+        .title_bottom(hint.centered())
+        .style(Style::default().bg(Color::Black));
+
+    Clear.render(popup, buf);
+    Paragraph::new(lines).block(block).render(popup, buf);
+}
+
+/// 名前などを入力するモーダル（長い名前は末尾が見えるように先頭を省く）
+fn render_name_input(title: &str, name: &str, confirm: &str, area: Rect, buf: &mut Buffer) {
+    const INPUT_WIDTH: usize = 40;
+    let mut visible = name.to_string();
+    while visible.width() >= INPUT_WIDTH {
+        visible.remove(0);
+    }
+    let input = pad_to_width(&format!("{}▏", visible), INPUT_WIDTH);
+    let lines = vec![Line::from(input.fg(Color::White))];
+    let hint = Line::from(format!(" Enter:{}  Esc:Cancel ", confirm).fg(Color::DarkGray));
+    render_dialog(title, lines, hint, area, buf);
+}
+
+/// 再生位置から決まる棒の高さで描くイコライザー風の飾り（同じ位置なら毎回同じ形）
+fn render_visualizer(progress_ms: i64, area: Rect, color: Color, buf: &mut Buffer) {
+    const MAX_BARS: u16 = 24;
+    const LEVELS: [&str; 9] = [" ", "▁", "▂", "▃", "▄", "▅", "▆", "▇", "█"];
+    // 1 本ごとに 1 列空ける
+    let bars = (area.width.saturating_sub(side_margin(area.width) * 2).div_ceil(2)).min(MAX_BARS);
+    if bars == 0 || area.height == 0 {
+        return;
+    }
+    let steps = area.height as usize * 8;
+    let left = area.x + (area.width - (bars * 2 - 1)) / 2;
+    let frame = progress_ms as f64 / 150.0;
+    for i in 0..bars {
+        // 周期の違う 2 つの波を足して、棒ごとにずらす
+        let phase = i as f64;
+        let wave = (frame * 0.9 + phase * 0.8).sin() + (frame * 0.37 + phase * 1.9).sin() * 0.6;
+        let level = ((wave + 1.6) / 3.2 * steps as f64).round().clamp(1.0, steps as f64) as usize;
+        for row in 0..area.height {
+            let filled = level.saturating_sub(row as usize * 8).min(8);
+            let y = area.bottom() - 1 - row;
+            buf.set_string(left + i * 2, y, LEVELS[filled], Style::default().fg(color));
+        }
+    }
+}
+
+/// スリープタイマーで止めた後の暗い画面（枕元の時計として今の時刻を出す）
+fn render_sleep_clock(area: Rect, buf: &mut Buffer) {
+    let (hour, minute) = local_time_of_day(SystemTime::now());
+    let clock = format!("{:02}:{:02}", hour, minute);
+    let lines = vec![
+        Line::from(clock.bold().dim().fg(Color::DarkGray)),
+        Line::from(""),
+        Line::from("Paused by the sleep timer".fg(Color::DarkGray)),
+        Line::from("press any key".fg(Color::DarkGray)),
+    ];
+    let rect = centered_rect(area.width, lines.len() as u16, area);
+    Paragraph::new(lines).centered().render(rect, buf);
+}
+
+/// 左右の余白（狭い端末では余白より中身を優先する）
+fn side_margin(width: u16) -> u16 {
+    if width >= 16 { 2 } else { 0 }
+}
+
+/// area の中央に width x height の領域を取る（はみ出す場合は area に収める）
+fn centered_rect(width: u16, height: u16, area: Rect) -> Rect {
+    let width = width.min(area.width);
+    let height = height.min(area.height);
+    Rect {
+        x: area.x + (area.width - width) / 2,
+        y: area.y + (area.height - height) / 2,
+        width,
+        height,
+    }
+}
+
+impl App {
+    /// 操作結果の通知（エラーは赤、成功は緑）
+    fn render_status_line(&self, area: Rect, buf: &mut Buffer) {
+        let Some(status) = &self.status_message else {
+            return;
+        };
+        let color = match status.kind {
+            StatusKind::Success => theme::accent(),
+            StatusKind::Error => Color::Red,
+        };
+        let text = truncate_with_ellipsis(&status.text, area.width as usize);
+        Paragraph::new(Line::from(text.fg(color)))
+            .centered()
+            .render(area, buf);
+    }
+
+    /// 現在のページのキー割り当てを中央にモーダル表示する
+    fn render_help_overlay(&self, area: Rect, buf: &mut Buffer) {
+        let custom_green = theme::accent();
+
+        let page_bindings = keymap::bindings_for(self.current_page);
+        let keys_width = page_bindings
+            .iter()
+            .chain(keymap::GLOBAL)
+            .map(|binding| binding.label().width())
+            .max()
+            .unwrap_or(0);
+        let binding_line = |binding: &KeyBinding| {
+            let keys = binding.label();
+            let padding = " ".repeat(keys_width - keys.width());
+            Line::from(vec![
+                format!(" {}{}  ", keys, padding).fg(custom_green).bold(),
+                binding.description.fg(Color::White),
+            ])
+        };
+
+        let mut lines: Vec<Line> = page_bindings.iter().map(binding_line).collect();
+        lines.push(Line::from(""));
+        lines.extend(keymap::GLOBAL.iter().map(binding_line));
+
+        render_popup(" Help ", lines, area, buf);
+    }
+
+    fn render_playlist_picker(&mut self, area: Rect, buf: &mut Buffer) {
+        let custom_green = theme::accent();
+        let Some(picker) = self.playlist_picker.as_mut() else {
+            return;
+        };
+
+        let names: Vec<&str> = picker
+            .playlists
+            .iter()
+            .map(|&i| self.playlists[i].name.as_str())
+            .collect();
+        let title = format!(" Add \"{}\" to ", picker.track_name);
+        let content_width = names.iter().map(|name| name.width()).max().unwrap_or(0) + 2;
+        let width = content_width.max(title.width()).max(32) as u16 + 4;
+        let popup = centered_rect(width, names.len() as u16 + 2, area);
+
+        let hint = match picker.confirm_duplicate {
+            Some(index) => Line::from(
+                format!(" Already in {} — add anyway? (y/n) ", self.playlists[index].name)
+                    .fg(Color::Yellow)
+                    .bold(),
+            ),
+            None => Line::from(" Enter:Add  Esc:Cancel ".fg(Color::DarkGray)),
+        };
+        let block = Block::bordered()
+            .border_set(border::ROUNDED)
+            .border_style(Style::default().fg(custom_green))
+            .title(Line::from(truncate_with_ellipsis(&title, width as usize).bold().fg(custom_green)).centered())
+            .title_bottom(hint.centered())
+            .style(Style::default().bg(Color::Black));
+
+        let items: Vec<ListItem> = names
+            .iter()
+            .map(|name| ListItem::new(name.to_string()).style(Style::default().fg(Color::White)))
+            .collect();
+        let list = List::new(items)
+            .block(block)
+            .highlight_style(
+                Style::default()
+                    .bg(custom_green)
+                    .fg(Color::Black)
+                    .bold()
+            )
+            .highlight_symbol("> ");
+
+        Clear.render(popup, buf);
+        ratatui::widgets::StatefulWidget::render(list, popup, buf, &mut picker.state);
+    }
+
+    /// 再生中の曲のアーティストを省略せずに表示する
+    fn render_artists_overlay(&self, area: Rect, buf: &mut Buffer) {
+        let Some(track) = &self.spotify_client.spotify_player.item else {
+            return;
+        };
+        let lines = track
+            .artist_list()
+            .into_iter()
+            .map(|name| Line::from(format!(" {} ", name).fg(Color::White)))
+            .collect();
+        render_popup(" Artists ", lines, area, buf);
+    }
+
+    fn render_track_info_overlay(&self, area: Rect, buf: &mut Buffer) {
+        let Some(track) = &self.spotify_client.spotify_player.item else {
+            return;
+        };
+        let mut rows = vec![("Title", track.name.clone())];
+        if !track.album.name.is_empty() {
+            rows.push(("Album", track.album.name.clone()));
+        }
+        if let Some(date) = &track.album.release_date {
+            rows.push(("Released", date.clone()));
+        }
+        if let Some(number) = track.track_number {
+            rows.push(("Track", format!("#{}", number)));
+        }
+        if let Some(popularity) = track.popularity {
+            rows.push(("Popularity", format!("{}/100", popularity)));
+        }
+        rows.push(("Length", format_time(track.duration_ms)));
+
+        let mut lines: Vec<Line> = rows
+            .into_iter()
+            .map(|(label, value)| {
+                Line::from(vec![
+                    format!(" {:<11}", label).fg(Color::DarkGray),
+                    format!("{} ", value).fg(Color::White),
+                ])
+            })
+            .collect();
+        // アーティストは全員を1行ずつ
+        for (i, name) in track.artist_list().into_iter().enumerate() {
+            let label = if i == 0 { "Artists" } else { "" };
+            lines.push(Line::from(vec![
+                format!(" {:<11}", label).fg(Color::DarkGray),
+                format!("{} ", name).fg(Color::White),
+            ]));
+        }
+        render_popup(" Track Info ", lines, area, buf);
+    }
+
+    /// Premium 限定の操作を受け付けるか（プロフィールが取れていなければ受け付ける）
+    fn is_premium(&self) -> bool {
+        self.spotify_client
+            .current_user
+            .as_ref()
+            .is_none_or(|user| user.is_premium())
+    }
+
+    /// 接続が切れている、またはレート制限で待っているときに表示するバッジ
+    fn connection_badge(&self) -> Option<Line<'static>> {
+        if let Some(until) = self.spotify_client.rate_limited_until() {
+            let wait = until.saturating_duration_since(Instant::now());
+            return Some(Line::from(
+                format!(" ⏳ Rate limited, retrying in {}s ", wait.as_secs_f32().ceil())
+                    .fg(Color::Yellow)
+                    .bold(),
+            ));
+        }
+        let text = match self.playback_error {
+            Some(ErrorKind::Offline) => Some(" ⚠ Offline (stale) "),
+            Some(ErrorKind::Auth) => Some(" ⚠ Not authorized, restart rs-pod "),
+            Some(ErrorKind::RateLimited) => Some(" ⏳ Rate limited (stale) "),
+            Some(ErrorKind::Api) => Some(" ⚠ Spotify error (stale) "),
+            None => None,
+        };
+        text.map(|text| Line::from(text.fg(Color::Red).bold()))
+    }
+
+    fn render_playlist_list(&mut self, area: Rect, buf: &mut Buffer) {
+        let custom_green = theme::accent();
+
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3), // ヘッダー
+                Constraint::Min(0),    // プレイリストリスト
+                Constraint::Length(1), // ステータス行
+                Constraint::Length(2), // フッター
+            ])
+            .split(area);
+
+        // ヘッダー
+        let title = Line::from(" Your Playlists ".bold().fg(custom_green));
+        let mut header_block = Block::bordered()
+            .border_set(border::ROUNDED)
+            .border_style(Style::default().fg(custom_green));
+        if let Some(user) = &self.spotify_client.current_user {
+            let text = truncate_with_ellipsis(&format!(" {} ", user.name()), area.width as usize / 3);
+            header_block = header_block.title_top(Line::from(text.fg(Color::DarkGray)));
+        }
+        if let Some(badge) = self.connection_badge() {
+            header_block = header_block.title_top(badge.right_aligned());
+        }
+        let header = Paragraph::new(title)
+            .centered()
+            .block(header_block);
+        header.render(layout[0], buf);
+
+        // プレイリストリスト（枠線とハイライト記号の分を除いた幅に収める）
+        let item_width = layout[1].width.saturating_sub(4) as usize;
+        let selected = self.playlist_state.selected();
+        let thumbnails = self.thumbnails.is_some();
+        self.playlist_row_height = if thumbnails { THUMBNAIL_HEIGHT } else { 1 };
+        // カバー画像の分は空けておき、リストを描いた後に上から描く
+        let indent = if thumbnails { " ".repeat(THUMBNAIL_WIDTH as usize + 1) } else { String::new() };
+        let items: Vec<ListItem> = self
+            .filtered_playlists
+            .iter()
+            .map(|&index| &self.playlists[index])
+            .enumerate()
+            .map(|(i, playlist)| {
+                let track_count = format!(" ({} tracks)", playlist.tracks.total);
+                // 画像を出すときは曲数を 2 行目にする
+                let name_width = if thumbnails {
+                    item_width.saturating_sub(indent.len())
+                } else {
+                    item_width.saturating_sub(track_count.width())
+                };
+                // 選択中の行だけ横スクロールし、それ以外は省略表示
+                let name = if selected == Some(i) {
+                    marquee(&playlist.name, name_width, self.marquee_tick)
+                } else {
+                    truncate_with_ellipsis(&playlist.name, name_width)
+                };
+                let text = if thumbnails {
+                    let track_count = format!("{} tracks", playlist.tracks.total);
+                    Text::from(vec![
+                        Line::from(format!("{}{}", indent, name)),
+                        Line::from(format!("{}{}", indent, track_count)),
+                    ])
+                } else {
+                    Text::from(format!("{}{}", name, track_count))
+                };
+                ListItem::new(text).style(Style::default().fg(Color::White))
+            })
+            .collect();
+
+        let mut list_block = Block::bordered()
+            .border_set(border::ROUNDED)
+            .border_style(Style::default().fg(custom_green));
+        if let Some(query) = &self.playlist_filter {
+            list_block = list_block.title(Line::from(vec![
+                " / ".fg(custom_green).bold(),
+                format!("{}▏ ", query).fg(Color::White),
+            ]));
+        }
+        let list = List::new(items)
+            .block(list_block)
+            .highlight_style(
+                Style::default()
+                    .bg(custom_green)
+                    .fg(Color::Black)
+                    .bold()
+            )
+            .highlight_symbol("> ")
+            // 画像の位置がずれないように、選択が無くても記号の幅を空ける
+            .highlight_spacing(HighlightSpacing::Always);
+
+        ratatui::widgets::StatefulWidget::render(list, layout[1], buf, &mut self.playlist_state);
+        self.playlist_list_area = layout[1].inner(Margin::new(1, 1));
+        self.list_page_size = (self.playlist_list_area.height / self.playlist_row_height) as usize;
+        self.render_playlist_thumbnails(buf);
+
+        // 読み込み中・失敗時はリストの中央に表示する
+        let placeholder = if self.playlists_task.is_some() {
+            Some(Line::from(
+                format!("{} Loading playlists…", SPINNER_FRAMES[self.spinner_frame]).fg(custom_green),
+            ))
+        } else {
+            self.playlists_error.as_ref().map(|e| {
+                Line::from(vec![
+                    format!("Failed to load playlists: {} ", e).fg(Color::Red),
+                    "(r: retry)".fg(Color::DarkGray),
+                ])
+            })
+        }
+        .or_else(|| {
+            (self.playlist_filter.is_some() && self.filtered_playlists.is_empty())
+                .then(|| Line::from("No matching playlists".fg(Color::DarkGray)))
+        });
+        if let Some(line) = placeholder {
+            let inner = layout[1].inner(Margin::new(1, 1));
+            let row = Rect { y: inner.y + inner.height / 2, height: 1.min(inner.height), ..inner };
+            Paragraph::new(line).centered().render(row, buf);
+        }
+
+        // フッター（操作ガイド）
+        let help = Line::from(vec![
+            "↑/k:Up ".fg(custom_green),
+            "↓/j:Down ".fg(custom_green),
+            "Enter:Play ".fg(custom_green),
+            "l:Open ".fg(custom_green),
+            "e:Enqueue ".fg(custom_green),
+            "n:New releases ".fg(custom_green),
+            "s:Search ".fg(custom_green),
+            "/:Filter ".fg(custom_green),
+            "[/]:Back/Fwd ".fg(custom_green),
+            "?:Help ".fg(custom_green),
+            "q:Quit".fg(custom_green),
+        ]);
+        let footer = Paragraph::new(help).centered();
+        footer.render(layout[3], buf);
+
+        self.render_status_line(layout[2], buf);
+    }
+
+    /// 見えている行の左端にカバー画像（取得中や画像の無いものは灰色の四角）
+    fn render_playlist_thumbnails(&mut self, buf: &mut Buffer) {
+        let Some(thumbnails) = self.thumbnails.as_mut() else {
+            return;
+        };
+        let area = self.playlist_list_area;
+        // ハイライト記号「> 」の右
+        let x = area.x + 2;
+        if area.width < 2 + THUMBNAIL_WIDTH {
+            return;
+        }
+        let rows = area.height / THUMBNAIL_HEIGHT;
+        let visible = self.filtered_playlists.iter().skip(self.playlist_state.offset()).take(rows as usize);
+        for (row, &index) in visible.enumerate() {
+            let rect = Rect {
+                x,
+                y: area.y + row as u16 * THUMBNAIL_HEIGHT,
+                width: THUMBNAIL_WIDTH,
+                height: THUMBNAIL_HEIGHT,
+            };
+            let drawn = match self.playlists[index].images.first() {
+                Some(image) => {
+                    thumbnails.request(&self.spotify_client, &image.url);
+                    thumbnails.render(&image.url, rect, buf)
+                }
+                None => false,
+            };
+            if !drawn {
+                buf.set_style(rect, Style::default().bg(Color::DarkGray));
+            }
+        }
+    }
+
+    fn render_now_playing(&mut self, area: Rect, buf: &mut Buffer) {
+        // カスタムカラーを定義
+        let custom_green = theme::accent();
+
+        if self.spotify_client.spotify_player.device.is_none() {
+            return self.render_no_device(area, buf);
+        }
+        // 固定の行が収まらない低い端末ではミニプレイヤー風の表示に切り替える
+        if area.height < NOW_PLAYING_MIN_HEIGHT {
+            return self.render_now_playing_compact(area, buf);
+        }
+
+        // 曲情報を取得
+        // 共演者が多いときは幅に収まる分だけ表示して残りは「+N more」
+        let (track_name, artist_names, duration_ms, explicit) = self
+            .spotify_client
+            .spotify_player
+            .item
+            .as_ref()
+            .map(|track| {
+                let artists = join_with_overflow(&track.artist_list(), area.width as usize);
+                (track.name.as_str(), artists, track.duration_ms, track.explicit)
+            })
+            .unwrap_or(("Press p to pick a playlist", String::new(), 0, false));
+        // 広告中は item が null のことが多いので、曲の代わりに広告と表示する
+        let is_ad = self.is_ad();
+        let has_item = self.spotify_client.spotify_player.item.is_some() || is_ad;
+        let (track_name, artist_names, explicit) = if is_ad {
+            ("Advertisement", String::new(), false)
+        } else {
+            (track_name, artist_names, explicit)
+        };
+
+        let progress_ms = self.interpolated_progress_ms();
+
+        // プログレスの計算
+        let progress_ratio = progress_percent(progress_ms, duration_ms);
+
+        // 時間表示の作成
+        let current_time = format_time(progress_ms);
+        let remaining_time = format!("-{}", format_time((duration_ms - progress_ms).max(0)));
+
+        // レイアウトを作成
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(1), // 上部の余白
+                Constraint::Length(1), // タイトル 1
+                Constraint::Length(1), // 区切り線 2
+                Constraint::Length(3), // 空行 3
+                Constraint::Length(1), // 曲名 4
+                Constraint::Length(1), // アーティスト名
+                Constraint::Length(2), // 空行
+                Constraint::Length(3), // プログレスバー（枠込み）
+                Constraint::Length(1), // 時間表示
+                Constraint::Min(0),    // 余白
+                Constraint::Length(1), // ステータス行
+                Constraint::Length(2), // フッター
+            ])
+            .split(area);
+
+        // 左上に再生中のデバイス、右上に接続状態
+        if let Some(device) = &self.spotify_client.spotify_player.device {
+            let text = format!(" Playing on: {}", device.name);
+            let text = truncate_with_ellipsis(&text, area.width as usize / 2);
+            Paragraph::new(Line::from(text.fg(Color::DarkGray))).render(layout[0], buf);
+        }
+        if let Some(badge) = self.connection_badge() {
+            Paragraph::new(badge.right_aligned()).render(layout[0], buf);
+        }
+
+        // タイトルに再生状態を表示（一時停止中と何も再生していないときを区別する）
+        let title = Line::from(playback_label(&self.spotify_client.spotify_player, is_ad).bold().fg(custom_green));
+        Paragraph::new(title).centered().render(layout[1], buf);
+
+        // タイトル行の右端にシャッフルとリピートの状態（オンは緑、オフは灰色）
+        let player = &self.spotify_client.spotify_player;
+        let ascii = self.config.ascii_icons || !locale_is_utf8();
+        let (shuffle_icon, repeat_icon) = match (ascii, player.repeat_state) {
+            (false, RepeatState::Track) => ("🔀", "🔂"),
+            (false, _) => ("🔀", "🔁"),
+            (true, RepeatState::Track) => ("S", "R1"),
+            (true, _) => ("S", "R"),
+        };
+        let mode_color = |on: bool| if on { custom_green } else { Color::DarkGray };
+        let modes = Line::from(vec![
+            shuffle_icon.fg(mode_color(player.shuffle_state)),
+            " ".into(),
+            repeat_icon.fg(mode_color(player.repeat_state != RepeatState::Off)),
+            " ".into(),
+        ]);
+        Paragraph::new(modes.right_aligned()).render(layout[1], buf);
+
+        // タイトル行の左端にスリープタイマーの残り時間
+        if let Some(sleep_at) = self.sleep_at {
+            let remaining = sleep_at.saturating_duration_since(Instant::now()).as_millis() as i64;
+            let text = format!(" Sleep {}", format_time(remaining));
+            Paragraph::new(Line::from(text.fg(Color::DarkGray))).render(layout[1], buf);
+        }
+
+        // 区切り線を表示
+        let separator_line = Line::from(self.separator.as_str().fg(custom_green));
+        Paragraph::new(separator_line).render(layout[2], buf);
+
+        // 曲名の上の空いた行にイコライザー風の飾り（一時停止中は再生位置が進まないので止まる）
+        if self.config.visualizer && has_item && !is_ad {
+            render_visualizer(progress_ms, layout[3], custom_green, buf);
+        }
+
+        // 曲名を表示
+        // centered() は文字数ではなく表示幅で寄せるので、全角文字を幅2で数えて
+        // 幅に収めた文字列を渡せば CJK の曲名もずれない（奇数の余りは右側に付く）
+        // 露骨な表現を含む曲は曲名の後ろに「E」バッジを付ける
+        let badge_width = if explicit { EXPLICIT_BADGE.width() + 1 } else { 0 };
+        let name_width = (area.width as usize).saturating_sub(badge_width);
+        let track_name = marquee(track_name, name_width, self.marquee_tick);
+        let mut track_line = Line::from(track_name.fg(custom_green).bold());
+        if explicit {
+            track_line.push_span(" ");
+            track_line.push_span(EXPLICIT_BADGE.fg(Color::Black).bg(Color::Gray));
+        }
+        Paragraph::new(track_line)
+            .centered()
+            .render(layout[4], buf);
+
+        // アーティスト名を表示
+        let artist_line = Line::from(artist_names.to_string().fg(custom_green));
+        Paragraph::new(artist_line)
+            .centered()
+            .render(layout[5], buf);
+
+        // 再生元のプレイリストやアルバム
+        if let Some(name) = self.context_name().filter(|_| !is_ad) {
+            let text = truncate_with_ellipsis(&format!("From: {}", name), area.width as usize);
+            Paragraph::new(Line::from(text.fg(Color::DarkGray)))
+                .centered()
+                .render(layout[6], buf);
+        }
+
+        // 何も再生していなければプログレスバーと時間は出さない
+        if has_item {
+            // プログレスバーのレイアウト
+            let margin = side_margin(area.width);
+            let progress_layout = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([
+                    Constraint::Length(margin), // 左の余白
+                    Constraint::Min(0),         // プログレスバー
+                    Constraint::Length(margin), // 右の余白
+                ])
+                .split(layout[7]);
+
+            // プログレスバーに枠を追加（広告中はシークできないので暗くしてクリックも無効）
+            let progress_color = if is_ad { Color::DarkGray } else { custom_green };
+            // Free プランではシークできないので枠だけ暗くする
+            let border_color = if self.is_premium() { progress_color } else { Color::DarkGray };
+            let progress_block = Block::bordered()
+                .border_set(border::ROUNDED)
+                .border_style(Style::default().fg(border_color));
+
+            let progress_inner = progress_block.inner(progress_layout[1]);
+            self.progress_area = if is_ad { Rect::default() } else { progress_inner };
+            progress_block.render(progress_layout[1], buf);
+
+            // 終わり間際は色を変えて次の曲に変わるのを知らせる
+            let gauge_color = if is_ad { progress_color } else { self.progress_color(duration_ms - progress_ms) };
+            let gauge = Gauge::default()
+                .gauge_style(Style::default().fg(gauge_color))
+                .percent(progress_ratio)
+                .label("");
+            gauge.render(progress_inner, buf);
+
+            // 時間表示のレイアウト
+            let time_layout = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([
+                    Constraint::Length(margin), // 左の余白
+                    Constraint::Min(0),         // 中央エリア
+                    Constraint::Length(margin), // 右の余白
+                ])
+                .split(layout[8]);
+            // 狭いときは再生時間と残り時間で半分ずつ
+            let time_width = (time_layout[1].width / 2).min(8);
+
+            // 中央エリアをさらに分割（再生時間とプログレスバーと残り時間の幅を揃える）
+            let time_inner_layout = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([
+                    Constraint::Length(time_width), // 再生時間
+                    Constraint::Min(0),             // 中央の余白
+                    Constraint::Length(time_width), // 残り時間
+                ])
+                .split(time_layout[1]);
+
+            // 再生時間を表示
+            let current_time_line = Line::from(current_time.fg(custom_green));
+            Paragraph::new(current_time_line).render(time_inner_layout[0], buf);
+
+            // 残り時間を表示
+            let remaining_time_line = Line::from(remaining_time.fg(custom_green));
+            Paragraph::new(remaining_time_line)
+                .alignment(Alignment::Right)
+                .render(time_inner_layout[2], buf);
+        } else {
+            self.progress_area = Rect::default();
+        }
+
+        // 音量バー（音量を変えられないデバイスでは暗くする）
+        if let Some(device) = &self.spotify_client.spotify_player.device
+            && let Some(volume) = device.volume_percent
+            && layout[9].height >= 2
+        {
+            let filled = (volume as usize * VOLUME_BAR_WIDTH).div_ceil(100).min(VOLUME_BAR_WIDTH);
+            let bar = format!(
+                "Vol {}{} {:>3}%",
+                "▮".repeat(filled),
+                "▯".repeat(VOLUME_BAR_WIDTH - filled),
+                volume
+            );
+            let color = if device.supports_volume && self.is_premium() {
+                custom_green
+            } else {
+                Color::DarkGray
+            };
+            let row = Rect { y: layout[9].y + 1, height: 1, ..layout[9] };
+            Paragraph::new(Line::from(bar.fg(color))).centered().render(row, buf);
+        }
+
+        // 余った高さにジャケット画像（音量バーの下に 1 行空けて、横幅は高さの 2 倍で正方形に見える）
+        if let Some(album_art) = self.album_art.as_mut()
+            && !is_ad
+            && layout[9].height >= 3 + ALBUM_ART_MIN_HEIGHT
+        {
+            let height = layout[9].height - 3;
+            let width = (height * 2).min(area.width);
+            let art_area = Rect {
+                x: area.x + (area.width - width) / 2,
+                y: layout[9].y + 3,
+                width,
+                height,
+            };
+            album_art.render(art_area, buf);
+        }
+
+        // フッター（操作ガイド）
+        // Free プランで使えない操作は灰色にする
+        let premium_color = if self.is_premium() { custom_green } else { Color::DarkGray };
+        let mut help_spans = Vec::new();
+        if !is_ad {
+            help_spans.push("←:Prev ".fg(custom_green));
+        }
+        help_spans.push("Space:Play/Pause ".fg(premium_color));
+        if !is_ad {
+            help_spans.push("→:Next ".fg(custom_green));
+        }
+        help_spans.extend([
+            "+/-:Volume ".fg(premium_color),
+            "p:Playlists ".fg(custom_green),
+            "Esc/[:Back ".fg(custom_green),
+        ]);
+        if let Some((_, position_ms)) = &self.resume_offer {
+            help_spans.push(format!("r:Resume {} ", format_time(*position_ms)).fg(premium_color).bold());
+        } else if let Some(last) = &self.last_played_offer {
+            let text = format!("r:Resume {} {} ", truncate_with_ellipsis(&last.name, 24), format_time(last.progress_ms));
+            help_spans.push(text.fg(premium_color).bold());
+        }
+        help_spans.push("?:Help ".fg(custom_green));
+        help_spans.push("q:Quit".fg(custom_green));
+        let help = Line::from(help_spans);
+        let footer = Paragraph::new(help).centered();
+        footer.render(layout[11], buf);
+
+        self.render_status_line(layout[10], buf);
+    }
+
+    /// プログレスバーの色（残りが theme.progress_ending_secs を切ったら progress_ending の色）
+    fn progress_color(&self, remaining_ms: i64) -> Color {
+        let theme = &self.config.theme;
+        let ending = theme.progress_ending_secs > 0 && remaining_ms < theme.progress_ending_secs as i64 * 1000;
+        let color = if ending { &theme.progress_ending } else { &theme.progress };
+        // 読めない色は既定の緑にする
+        color.parse().map(theme::adapt).unwrap_or_else(|_| theme::accent())
+    }
+
+    /// 区切り線を幅に合わせる（毎フレーム作らず、端末の幅が変わったときだけ作り直す）
+    fn resize_separator(&mut self, width: u16) {
+        if self.separator.chars().count() != width as usize {
+            self.separator = "─".repeat(width as usize);
+        }
+    }
+
+    /// 低い端末用の表示（「▶ Artist — Title [=====   ] 1:23/3:45」の 1 行と通知・操作ガイド）
+    fn render_now_playing_compact(&mut self, area: Rect, buf: &mut Buffer) {
+        let custom_green = theme::accent();
+        // バーが小さすぎてクリックでのシークは当てにならないので無効にする
+        self.progress_area = Rect::default();
+        if area.height == 0 || area.width == 0 {
+            return;
+        }
+
+        let row = Rect { height: 1, ..area };
+        let is_ad = self.is_ad();
+        let player = &self.spotify_client.spotify_player;
+        let (title, duration_ms) = match &player.item {
+            _ if is_ad => ("Advertisement".to_string(), 0),
+            Some(track) => {
+                let artists = join_with_overflow(&track.artist_list(), area.width as usize / 2);
+                (format!("{} — {}", artists, track.name), track.duration_ms)
+            }
+            None => {
+                let label = playback_label(player, is_ad).trim();
+                Paragraph::new(Line::from(vec![
+                    label.fg(custom_green).bold(),
+                    " — press p to pick a playlist".fg(Color::DarkGray),
+                ]))
+                .render(row, buf);
+                return self.render_compact_footer(area, buf);
+            }
+        };
+
+        let ascii = self.config.ascii_icons || !locale_is_utf8();
+        let icon = match (ascii, player.is_playing) {
+            (false, true) => "▶ ",
+            (false, false) => "⏸ ",
+            (true, true) => "> ",
+            (true, false) => "|| ",
+        };
+        let progress_ms = self.interpolated_progress_ms();
+        let times = format!(" {}/{}", format_time(progress_ms), format_time(duration_ms));
+
+        // バーは残りの幅の 1/3 まで（狭すぎれば出さない）
+        let available = (area.width as usize).saturating_sub(icon.width() + times.width());
+        let bar_width = (available / 3).saturating_sub(3).min(COMPACT_BAR_WIDTH);
+        let bar = if bar_width >= 3 {
+            let filled = (progress_percent(progress_ms, duration_ms) as usize * bar_width / 100).min(bar_width);
+            format!(" [{}{}]", "=".repeat(filled), " ".repeat(bar_width - filled))
+        } else {
+            String::new()
+        };
+        let title_width = available.saturating_sub(bar.width());
+        let title = marquee(&title, title_width, self.marquee_tick);
+
+        let color = if is_ad { Color::DarkGray } else { custom_green };
+        let bar_color = if is_ad { color } else { self.progress_color(duration_ms - progress_ms) };
+        Paragraph::new(Line::from(vec![icon.fg(color), title.fg(color).bold()])).render(row, buf);
+        Paragraph::new(Line::from(vec![bar.fg(bar_color), times.fg(color)]).right_aligned()).render(row, buf);
+
+        self.render_compact_footer(area, buf);
+    }
+
+    /// 1 行表示の下に通知と短い操作ガイドを入る分だけ出す
+    fn render_compact_footer(&self, area: Rect, buf: &mut Buffer) {
+        let custom_green = theme::accent();
+        let bottom = area.y + area.height;
+        if area.height >= 3 {
+            let help = Line::from("Space:Play/Pause ←/→:Prev/Next ?:Help q:Quit".fg(custom_green));
+            let row = Rect { y: bottom - 1, height: 1, ..area };
+            Paragraph::new(help).centered().render(row, buf);
+            self.render_status_line(Rect { y: bottom - 2, height: 1, ..area }, buf);
+        } else if area.height == 2 {
+            self.render_status_line(Rect { y: bottom - 1, height: 1, ..area }, buf);
+        }
+    }
+
+    /// どのデバイスでも Spotify が開かれていないときの案内
+    fn render_no_device(&mut self, area: Rect, buf: &mut Buffer) {
+        let custom_green = theme::accent();
+
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(1), // 上部の余白
+                Constraint::Length(1), // タイトル
+                Constraint::Length(1), // 区切り線
+                Constraint::Min(0),    // 案内
+                Constraint::Length(1), // ステータス行
+                Constraint::Length(2), // フッター
+            ])
+            .split(area);
+
+        if let Some(badge) = self.connection_badge() {
+            Paragraph::new(badge.right_aligned()).render(layout[0], buf);
+        }
+
+        let title = Line::from(" Now Playing ".bold().fg(custom_green));
+        Paragraph::new(title).centered().render(layout[1], buf);
+
+        Paragraph::new(Line::from(self.separator.as_str().fg(custom_green))).render(layout[2], buf);
+
+        let lines = vec![
+            Line::from("No active device".bold().fg(custom_green)),
+            Line::from(""),
+            Line::from("Open Spotify on your phone, computer or web player,".fg(Color::White)),
+            Line::from("or press d to pick one of your devices and start playback here.".fg(Color::White)),
+        ];
+        let height = (lines.len() as u16).min(layout[3].height);
+        let message_area = Rect {
+            y: layout[3].y + (layout[3].height - height) / 2,
+            height,
+            ..layout[3]
+        };
+        Paragraph::new(lines)
             .centered()
-            .block(header_block);
-        header.render(layout[0], buf);
+            .render(message_area, buf);
 
-        // プレイリストリスト
-        let items: Vec<ListItem> = self
-            .playlists
+        let help = Line::from(vec![
+            "d:Devices ".fg(custom_green).bold(),
+            "p:Playlists ".fg(custom_green),
+            "?:Help ".fg(custom_green),
+            "q:Quit".fg(custom_green),
+        ]);
+        Paragraph::new(help).centered().render(layout[5], buf);
+
+        self.render_status_line(layout[4], buf);
+    }
+
+    fn render_devices(&mut self, area: Rect, buf: &mut Buffer) {
+        let custom_green = theme::accent();
+
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3), // ヘッダー
+                Constraint::Min(0),    // デバイスのリスト
+                Constraint::Length(1), // ステータス行
+                Constraint::Length(2), // フッター
+            ])
+            .split(area);
+
+        let header_block = Block::bordered()
+            .border_set(border::ROUNDED)
+            .border_style(Style::default().fg(custom_green));
+        Paragraph::new(Line::from(" Devices ".bold().fg(custom_green)))
+            .centered()
+            .block(header_block)
+            .render(layout[0], buf);
+
+        let list_block = Block::bordered()
+            .border_set(border::ROUNDED)
+            .border_style(Style::default().fg(custom_green));
+
+        if self.devices.is_empty() {
+            let inner = list_block.inner(layout[1]);
+            list_block.render(layout[1], buf);
+            let line = Line::from("No devices found. Open Spotify somewhere, then press r.".fg(Color::DarkGray));
+            let row = Rect { y: inner.y + inner.height / 2, height: 1.min(inner.height), ..inner };
+            Paragraph::new(line).centered().render(row, buf);
+        } else {
+            let item_width = layout[1].width.saturating_sub(4) as usize;
+            let items: Vec<ListItem> = self
+                .devices
+                .iter()
+                .map(|device| {
+                    let mut text = device.name.clone();
+                    if device.is_active {
+                        text.push_str(" (active)");
+                    }
+                    let color = if device.id.is_some() { Color::White } else { Color::DarkGray };
+                    ListItem::new(truncate_with_ellipsis(&text, item_width))
+                        .style(Style::default().fg(color))
+                })
+                .collect();
+
+            let list = List::new(items)
+                .block(list_block)
+                .highlight_style(
+                    Style::default()
+                        .bg(custom_green)
+                        .fg(Color::Black)
+                        .bold()
+                )
+                .highlight_symbol("> ");
+
+            ratatui::widgets::StatefulWidget::render(list, layout[1], buf, &mut self.device_state);
+        }
+
+        let help = Line::from(vec![
+            "↑↓/jk:Move ".fg(custom_green),
+            "Enter:Play here ".fg(custom_green),
+            "r:Reload ".fg(custom_green),
+            "Esc:Back".fg(custom_green),
+        ]);
+        Paragraph::new(help).centered().render(layout[3], buf);
+
+        self.render_status_line(layout[2], buf);
+        self.list_page_size = layout[1].height.saturating_sub(2) as usize;
+    }
+
+    fn render_queue_preview(&mut self, area: Rect, buf: &mut Buffer) {
+        let custom_green = theme::accent();
+        let Some(preview) = self.queue_preview.as_mut() else {
+            return;
+        };
+
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3), // ヘッダー
+                Constraint::Min(0),    // 追加する曲のリスト
+                Constraint::Length(1), // ステータス行
+                Constraint::Length(2), // フッター
+            ])
+            .split(area);
+
+        // ヘッダー
+        let mut title = Line::from(vec![
+            " Queue Preview ".bold().fg(custom_green),
+            format!("· {} · {} tracks ", preview.playlist_name, preview.entries.len()).fg(Color::White),
+        ]);
+        if preview.unavailable > 0 {
+            title.push_span(format!("({} unavailable) ", preview.unavailable).fg(Color::DarkGray));
+        }
+        let header_block = Block::bordered()
+            .border_set(border::ROUNDED)
+            .border_style(Style::default().fg(custom_green));
+        Paragraph::new(title)
+            .centered()
+            .block(header_block)
+            .render(layout[0], buf);
+
+        // 追加する順に番号を振る（外したものは番号なし）
+        let item_width = layout[1].width.saturating_sub(4) as usize;
+        let columns = self.config.track_columns;
+        let mut order = 0;
+        let items: Vec<ListItem> = preview
+            .entries
             .iter()
-            .map(|playlist| {
-                let track_count = format!(" ({} tracks)", playlist.tracks.total);
-                ListItem::new(format!("{}{}", playlist.name, track_count))
-                    .style(Style::default().fg(Color::White))
+            .map(|(track, included)| {
+                let (label, color) = if *included {
+                    order += 1;
+                    (format!("{:>3}. ", order), Color::White)
+                } else {
+                    ("  -  ".to_string(), Color::DarkGray)
+                };
+                let text = if columns {
+                    let columns_width = item_width.saturating_sub(label.width());
+                    let row = track_columns(&list_name(track), &track.artist_names(), track.duration_ms, columns_width);
+                    format!("{}{}", label, row)
+                } else {
+                    format!("{}{} - {}", label, list_name(track), track.artist_names())
+                };
+                ListItem::new(truncate_with_ellipsis(&text, item_width))
+                    .style(Style::default().fg(color))
             })
             .collect();
 
@@ -238,154 +3243,405 @@ impl App {
             )
             .highlight_symbol("> ");
 
-        ratatui::widgets::StatefulWidget::render(list, layout[1], buf, &mut self.playlist_state);
+        ratatui::widgets::StatefulWidget::render(list, layout[1], buf, &mut preview.state);
 
-        // フッター（操作ガイド）
-        let help = Line::from(vec![
-            "↑/k:Up ".fg(custom_green),
-            "↓/j:Down ".fg(custom_green),
-            "Enter:Play ".fg(custom_green),
-            "q:Quit".fg(custom_green),
-        ]);
-        let footer = Paragraph::new(help).centered();
-        footer.render(layout[2], buf);
+        // フッター（進捗または操作ガイド）
+        let help = if let Some(error) = &preview.error {
+            Line::from(format!("Stopped: {}", error).fg(Color::Red))
+        } else if preview.is_enqueuing() {
+            let (queued, total) = preview.progress().unwrap_or_default();
+            Line::from(vec![
+                format!("Queueing {}/{} ", queued, total).fg(custom_green).bold(),
+                "Esc:Cancel".fg(custom_green),
+            ])
+        } else if let Some((queued, _)) = preview.progress() {
+            Line::from(vec![
+                format!("Queued {} tracks ", queued).fg(custom_green).bold(),
+                "Esc:Back".fg(custom_green),
+            ])
+        } else {
+            Line::from(vec![
+                "↑↓/jk:Move ".fg(custom_green),
+                "J/K:Reorder ".fg(custom_green),
+                "Space:Toggle ".fg(custom_green),
+                "Enter:Enqueue ".fg(custom_green),
+                "Esc:Back".fg(custom_green),
+            ])
+        };
+        Paragraph::new(help).centered().render(layout[3], buf);
+
+        self.render_status_line(layout[2], buf);
+        self.list_page_size = layout[1].height.saturating_sub(2) as usize;
     }
 
-    fn render_now_playing(&self, area: Rect, buf: &mut Buffer) {
-        // カスタムカラーを定義
-        let custom_green = Color::Rgb(0x0A, 0xE1, 0x64);
+    fn render_playlist_tracks(&mut self, area: Rect, buf: &mut Buffer) {
+        let custom_green = theme::accent();
+        let Some(view) = self.playlist_view.as_mut() else {
+            return;
+        };
 
-        // 曲情報を取得
-        let (track_name, artist_names, duration_ms) = self
-            .spotify_client
-            .spotify_player
-            .item
-            .as_ref()
-            .map(|track| {
-                let artists = track
-                    .artists
-                    .iter()
-                    .map(|a| a.name.as_str())
-                    .collect::<Vec<_>>()
-                    .join(", ");
-                (track.name.as_str(), artists, track.duration_ms)
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(4), // ヘッダー（名前と作成者・説明）
+                Constraint::Min(0),    // 曲のリスト
+                Constraint::Length(1), // ステータス行
+                Constraint::Length(2), // フッター
+            ])
+            .split(area);
+
+        // ヘッダー
+        let inner_width = layout[0].width.saturating_sub(4) as usize;
+        let title = Line::from(truncate_with_ellipsis(&view.playlist.name, inner_width).bold().fg(custom_green));
+        let mut subtitle = Vec::new();
+        if let Some(owner) = view.owner_name() {
+            subtitle.push(format!("by {}", owner));
+        }
+        subtitle.push(format!("{} tracks", view.tracks.len()));
+        subtitle.push(format_duration_long(view.total_duration_ms()));
+        if let Some(followers) = &view.playlist.followers {
+            let unit = if followers.total == 1 { "follower" } else { "followers" };
+            subtitle.push(format!("{} {}", followers.total, unit));
+        }
+        if view.unavailable > 0 {
+            subtitle.push(format!("{} unavailable", view.unavailable));
+        }
+        if let Some(description) = view.description() {
+            subtitle.push(description);
+        }
+        let subtitle = truncate_with_ellipsis(&subtitle.join(" · "), inner_width);
+        let mut header_block = Block::bordered()
+            .border_set(border::ROUNDED)
+            .border_style(Style::default().fg(custom_green));
+        if let Some(badge) = library_badge(view.following, "✓ Following", "f:Follow", custom_green) {
+            header_block = header_block.title_top(badge.right_aligned());
+        }
+        Paragraph::new(vec![title, Line::from(subtitle.fg(Color::DarkGray))])
+            .centered()
+            .block(header_block)
+            .render(layout[0], buf);
+
+        // 曲のリスト
+        let item_width = layout[1].width.saturating_sub(4) as usize;
+        let columns = self.config.track_columns;
+        // 再生中の曲は番号の代わりに印を付ける（選択の > とは別に見えるように）
+        let playing_id = self.spotify_client.spotify_player.item.as_ref().and_then(|track| track.id.as_deref());
+        let playing_marker = if self.config.ascii_icons || !locale_is_utf8() { "  *  " } else { "  ▶  " };
+        let items: Vec<ListItem> = view
+            .tracks
+            .iter()
+            .enumerate()
+            .map(|(i, track)| {
+                let playing = playing_id.is_some() && track.id.as_deref() == playing_id;
+                let label = if playing { playing_marker.to_string() } else { format!("{:>3}. ", i + 1) };
+                let text = if columns {
+                    let columns_width = item_width.saturating_sub(label.width());
+                    let row = track_columns(&list_name(track), &track.artist_names(), track.duration_ms, columns_width);
+                    format!("{}{}", label, row)
+                } else {
+                    format!("{}{} - {}", label, list_name(track), track.artist_names())
+                };
+                let style = if playing {
+                    Style::default().fg(custom_green).bold()
+                } else {
+                    Style::default().fg(Color::White)
+                };
+                ListItem::new(truncate_with_ellipsis(&text, item_width)).style(style)
             })
-            .unwrap_or(("No track playing", String::new(), 0));
+            .collect();
+
+        let list = List::new(items)
+            .block(
+                Block::bordered()
+                    .border_set(border::ROUNDED)
+                    .border_style(Style::default().fg(custom_green))
+            )
+            .highlight_style(
+                Style::default()
+                    .bg(custom_green)
+                    .fg(Color::Black)
+                    .bold()
+            )
+            .highlight_symbol("> ");
 
-        let progress_ms = self.spotify_client.spotify_player.progress_ms.unwrap_or(0);
+        ratatui::widgets::StatefulWidget::render(list, layout[1], buf, &mut view.state);
 
-        // プログレスの計算
-        let progress_ratio = if duration_ms > 0 {
-            (progress_ms as f64 / duration_ms as f64 * 100.0) as u16
-        } else {
-            0
-        };
+        // 削除は取り消せないので確認する
+        if view.confirm_remove
+            && let Some(track) = view.selected_track()
+        {
+            let question = format!("Remove \"{}\" from this playlist?", track.name);
+            let question = truncate_with_ellipsis(&question, area.width.saturating_sub(4) as usize);
+            let lines = vec![Line::from(question.fg(Color::Yellow).bold())];
+            let hint = Line::from(" y:Remove  n:Cancel ".fg(Color::DarkGray));
+            render_dialog(" Remove track ", lines, hint, area, buf);
+        }
 
-        // 時間表示の作成
-        let current_time = format_time(progress_ms);
-        let remaining_time = format!("-{}", format_time(duration_ms - progress_ms));
+        // フッター
+        let help = Line::from(vec![
+            "↑↓/jk:Move ".fg(custom_green),
+            "Enter:Play from here ".fg(custom_green),
+            "e:Enqueue ".fg(custom_green),
+            "f:Follow ".fg(custom_green),
+            "d:Remove ".fg(custom_green),
+            "K/J:Reorder ".fg(custom_green),
+            "Esc:Back".fg(custom_green),
+        ]);
+        Paragraph::new(help).centered().render(layout[3], buf);
+
+        self.render_status_line(layout[2], buf);
+        self.list_page_size = layout[1].height.saturating_sub(2) as usize;
+    }
+
+    fn render_search(&mut self, area: Rect, buf: &mut Buffer) {
+        let custom_green = theme::accent();
 
-        // レイアウトを作成
         let layout = Layout::default()
             .direction(Direction::Vertical)
             .constraints([
-                Constraint::Length(1), // 上部の余白
-                Constraint::Length(1), // タイトル 1
-                Constraint::Length(1), // 区切り線 2
-                Constraint::Length(3), // 空行 3
-                Constraint::Length(1), // 曲名 4
-                Constraint::Length(1), // アーティスト名
-                Constraint::Length(2), // 空行
-                Constraint::Length(3), // プログレスバー（枠込み）
-                Constraint::Length(1), // 時間表示
-                Constraint::Min(0),    // 余白
+                Constraint::Length(3), // 検索語の入力
+                Constraint::Min(0),    // 結果のリスト
+                Constraint::Length(1), // ステータス行
                 Constraint::Length(2), // フッター
             ])
             .split(area);
 
-        // タイトルを表示
-        let title = Line::from(" Now Playing ".bold().fg(custom_green));
-        Paragraph::new(title).centered().render(layout[1], buf);
+        // 入力欄（検索中は右上にスピナー）
+        let mut input_block = Block::bordered()
+            .border_set(border::ROUNDED)
+            .border_style(Style::default().fg(custom_green))
+            .title(Line::from(" Search ".bold().fg(custom_green)));
+        if self.search.is_searching() {
+            let spinner = format!(" {} ", SPINNER_FRAMES[self.spinner_frame]);
+            input_block = input_block.title_top(Line::from(spinner.fg(custom_green)).right_aligned());
+        }
+        let input_width = layout[0].width.saturating_sub(4) as usize;
+        // 長い入力は末尾が見えるように先頭側を切る
+        let mut query = self.search.query.clone();
+        while query.width() + 1 > input_width && !query.is_empty() {
+            query.remove(0);
+        }
+        Paragraph::new(Line::from(format!(" {}▏", query).fg(Color::White)))
+            .block(input_block)
+            .render(layout[0], buf);
 
-        // 区切り線を表示
-        let separator = "─".repeat(area.width as usize);
-        let separator_line = Line::from(separator.fg(custom_green));
-        Paragraph::new(separator_line).render(layout[2], buf);
+        let list_block = Block::bordered()
+            .border_set(border::ROUNDED)
+            .border_style(Style::default().fg(custom_green));
+        let placeholder = if let Some(error) = &self.search.error {
+            Some(Line::from(error.clone().fg(Color::Red)))
+        } else if self.search.query.trim().is_empty() {
+            Some(Line::from("Type to search for tracks".fg(Color::DarkGray)))
+        } else if self.search.results.is_empty() && !self.search.is_searching() {
+            Some(Line::from("No results".fg(Color::DarkGray)))
+        } else {
+            None
+        };
 
-        // 曲名を表示
-        let track_line = Line::from(track_name.to_string().fg(custom_green).bold());
-        Paragraph::new(track_line)
-            .centered()
-            .render(layout[4], buf);
+        if let Some(line) = placeholder {
+            let inner = list_block.inner(layout[1]);
+            list_block.render(layout[1], buf);
+            let row = Rect { y: inner.y + inner.height / 2, height: 1.min(inner.height), ..inner };
+            Paragraph::new(line).centered().render(row, buf);
+        } else {
+            let item_width = layout[1].width.saturating_sub(4) as usize;
+            let columns = self.config.track_columns;
+            let items: Vec<ListItem> = self
+                .search
+                .results
+                .iter()
+                .map(|track| {
+                    let text = if columns {
+                        track_columns(&list_name(track), &track.artist_names(), track.duration_ms, item_width)
+                    } else {
+                        format!("{} - {}", list_name(track), track.artist_names())
+                    };
+                    ListItem::new(truncate_with_ellipsis(&text, item_width))
+                        .style(Style::default().fg(Color::White))
+                })
+                .collect();
 
-        // アーティスト名を表示
-        let artist_line = Line::from(artist_names.to_string().fg(custom_green));
-        Paragraph::new(artist_line)
-            .centered()
-            .render(layout[5], buf);
+            let list = List::new(items)
+                .block(list_block)
+                .highlight_style(
+                    Style::default()
+                        .bg(custom_green)
+                        .fg(Color::Black)
+                        .bold()
+                )
+                .highlight_symbol("> ");
+
+            ratatui::widgets::StatefulWidget::render(list, layout[1], buf, &mut self.search.state);
+        }
+
+        let help = Line::from(vec![
+            "↑↓:Move ".fg(custom_green),
+            "Enter:Play now ".fg(custom_green),
+            "Ctrl-E:Play next ".fg(custom_green),
+            "Esc:Back".fg(custom_green),
+        ]);
+        Paragraph::new(help).centered().render(layout[3], buf);
+
+        self.render_status_line(layout[2], buf);
+        self.list_page_size = layout[1].height.saturating_sub(2) as usize;
+    }
 
-        // プログレスバーのレイアウト
-        let progress_layout = Layout::default()
-            .direction(Direction::Horizontal)
+    fn render_new_releases(&mut self, area: Rect, buf: &mut Buffer) {
+        let custom_green = theme::accent();
+
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
             .constraints([
-                Constraint::Length(2), // 左の余白
-                Constraint::Min(0),    // プログレスバー
-                Constraint::Length(2), // 右の余白
+                Constraint::Length(3), // ヘッダー
+                Constraint::Min(0),    // アルバムのリスト
+                Constraint::Length(1), // ステータス行
+                Constraint::Length(2), // フッター
             ])
-            .split(layout[7]);
+            .split(area);
+
+        let header_block = Block::bordered()
+            .border_set(border::ROUNDED)
+            .border_style(Style::default().fg(custom_green));
+        Paragraph::new(Line::from(" New Releases ".bold().fg(custom_green)))
+            .centered()
+            .block(header_block)
+            .render(layout[0], buf);
 
-        // プログレスバーに枠を追加
-        let progress_block = Block::bordered()
+        let list_block = Block::bordered()
             .border_set(border::ROUNDED)
             .border_style(Style::default().fg(custom_green));
 
-        let progress_inner = progress_block.inner(progress_layout[1]);
-        progress_block.render(progress_layout[1], buf);
+        if self.new_releases.is_empty() {
+            let inner = list_block.inner(layout[1]);
+            list_block.render(layout[1], buf);
+            let line = Line::from("No new releases".fg(Color::DarkGray));
+            let row = Rect { y: inner.y + inner.height / 2, height: 1.min(inner.height), ..inner };
+            Paragraph::new(line).centered().render(row, buf);
+        } else {
+            let item_width = layout[1].width.saturating_sub(4) as usize;
+            let items: Vec<ListItem> = self
+                .new_releases
+                .iter()
+                .map(|album| {
+                    let mut text = format!("{} — {}", album.name, album.artist_names());
+                    if let Some(year) = album.release_year() {
+                        text.push_str(&format!(" ({})", year));
+                    }
+                    ListItem::new(truncate_with_ellipsis(&text, item_width))
+                        .style(Style::default().fg(Color::White))
+                })
+                .collect();
 
-        let gauge = Gauge::default()
-            .gauge_style(Style::default().fg(custom_green))
-            .percent(progress_ratio)
-            .label("");
-        gauge.render(progress_inner, buf);
+            let list = List::new(items)
+                .block(list_block)
+                .highlight_style(
+                    Style::default()
+                        .bg(custom_green)
+                        .fg(Color::Black)
+                        .bold()
+                )
+                .highlight_symbol("> ");
 
-        // 時間表示のレイアウト
-        let time_layout = Layout::default()
-            .direction(Direction::Horizontal)
-            .constraints([
-                Constraint::Length(2), // 左の余白
-                Constraint::Min(0),    // 中央エリア
-                Constraint::Length(2), // 右の余白
-            ])
-            .split(layout[8]);
+            ratatui::widgets::StatefulWidget::render(list, layout[1], buf, &mut self.new_releases_state);
+        }
 
-        // 中央エリアをさらに分割（再生時間とプログレスバーと残り時間の幅を揃える）
-        let time_inner_layout = Layout::default()
-            .direction(Direction::Horizontal)
+        let help = Line::from(vec![
+            "↑↓/jk:Move ".fg(custom_green),
+            "Enter:Open ".fg(custom_green),
+            "R:Refresh ".fg(custom_green),
+            "Esc:Back".fg(custom_green),
+        ]);
+        Paragraph::new(help).centered().render(layout[3], buf);
+
+        self.render_status_line(layout[2], buf);
+        self.list_page_size = layout[1].height.saturating_sub(2) as usize;
+    }
+
+    fn render_album_tracks(&mut self, area: Rect, buf: &mut Buffer) {
+        let custom_green = theme::accent();
+        let Some(view) = self.album_view.as_mut() else {
+            return;
+        };
+
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
             .constraints([
-                Constraint::Length(8), // 再生時間
-                Constraint::Min(0),    // 中央の余白
-                Constraint::Length(8), // 残り時間
+                Constraint::Length(4), // ヘッダー（名前とアーティスト）
+                Constraint::Min(0),    // 曲のリスト
+                Constraint::Length(1), // ステータス行
+                Constraint::Length(2), // フッター
             ])
-            .split(time_layout[1]);
+            .split(area);
 
-        // 再生時間を表示
-        let current_time_line = Line::from(current_time.fg(custom_green));
-        Paragraph::new(current_time_line).render(time_inner_layout[0], buf);
+        // ヘッダー
+        let inner_width = layout[0].width.saturating_sub(4) as usize;
+        let title = Line::from(truncate_with_ellipsis(&view.album.name, inner_width).bold().fg(custom_green));
+        let mut subtitle = vec![format!("by {}", view.album.artist_names())];
+        if let Some(year) = view.album.release_year() {
+            subtitle.push(year.to_string());
+        }
+        subtitle.push(format!("{} tracks", view.tracks.len()));
+        subtitle.push(format_duration_long(view.total_duration_ms()));
+        let subtitle = truncate_with_ellipsis(&subtitle.join(" · "), inner_width);
+        let mut header_block = Block::bordered()
+            .border_set(border::ROUNDED)
+            .border_style(Style::default().fg(custom_green));
+        if let Some(badge) = library_badge(view.saved, "♥ Saved", "s:Save", custom_green) {
+            header_block = header_block.title_top(badge.right_aligned());
+        }
+        Paragraph::new(vec![title, Line::from(subtitle.fg(Color::DarkGray))])
+            .centered()
+            .block(header_block)
+            .render(layout[0], buf);
 
-        // 残り時間を表示
-        let remaining_time_line = Line::from(remaining_time.fg(custom_green));
-        Paragraph::new(remaining_time_line)
-            .alignment(Alignment::Right)
-            .render(time_inner_layout[2], buf);
+        // 曲のリスト（番号はアルバムの曲順）
+        let item_width = layout[1].width.saturating_sub(4) as usize;
+        let columns = self.config.track_columns;
+        let items: Vec<ListItem> = view
+            .tracks
+            .iter()
+            .enumerate()
+            .map(|(i, track)| {
+                let number = track.track_number.map_or(i + 1, |n| n as usize);
+                let label = format!("{:>3}. ", number);
+                let text = if columns {
+                    let columns_width = item_width.saturating_sub(label.width());
+                    let row = track_columns(&list_name(track), &track.artist_names(), track.duration_ms, columns_width);
+                    format!("{}{}", label, row)
+                } else {
+                    format!("{}{} - {}", label, list_name(track), track.artist_names())
+                };
+                ListItem::new(truncate_with_ellipsis(&text, item_width))
+                    .style(Style::default().fg(Color::White))
+            })
+            .collect();
 
-        // フッター（操作ガイド）
+        let list = List::new(items)
+            .block(
+                Block::bordered()
+                    .border_set(border::ROUNDED)
+                    .border_style(Style::default().fg(custom_green))
+            )
+            .highlight_style(
+                Style::default()
+                    .bg(custom_green)
+                    .fg(Color::Black)
+                    .bold()
+            )
+            .highlight_symbol("> ");
+
+        ratatui::widgets::StatefulWidget::render(list, layout[1], buf, &mut view.state);
+
+        // フッター
         let help = Line::from(vec![
-            "←:Prev ".fg(custom_green),
-            "→:Next ".fg(custom_green),
-            "p/Esc:Playlists ".fg(custom_green),
-            "q:Quit".fg(custom_green),
+            "↑↓/jk:Move ".fg(custom_green),
+            "Enter:Play from here ".fg(custom_green),
+            "s:Save ".fg(custom_green),
+            "Esc:Back".fg(custom_green),
         ]);
-        let footer = Paragraph::new(help).centered();
-        footer.render(layout[10], buf);
+        Paragraph::new(help).centered().render(layout[3], buf);
+
+        self.render_status_line(layout[2], buf);
+        self.list_page_size = layout[1].height.saturating_sub(2) as usize;
     }
 }
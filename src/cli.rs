@@ -0,0 +1,117 @@
+use clap::{Parser, Subcommand, ValueEnum};
+use color_eyre::{eyre::eyre, Result};
+use rs_pod::{
+    api::{oauth::ProvidedToken, spotify::SkipDirection},
+    config::Config,
+    keymap,
+    utils::format_time,
+    SpotifyClient, SpotifyPlayer,
+};
+use std::path::PathBuf;
+
+/// Spotify player for the terminal. Launches the interactive UI when no command is given.
+#[derive(Debug, Parser)]
+#[command(version, about)]
+pub struct Cli {
+    /// Use an existing access token instead of the browser login (or set SPOTIFY_ACCESS_TOKEN)
+    #[arg(long, global = true)]
+    pub token: Option<String>,
+
+    /// Write debug logs to this file (nothing is logged while the UI is open otherwise)
+    #[arg(long, global = true, value_name = "PATH")]
+    pub log_file: Option<PathBuf>,
+
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Skip to the next track
+    Next,
+    /// Pause playback
+    Pause,
+    /// Print the currently playing track
+    Now {
+        /// Print as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Print the keybinding reference
+    Keys {
+        #[arg(value_enum, default_value_t = KeysFormat::Markdown)]
+        format: KeysFormat,
+    },
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum KeysFormat {
+    Markdown,
+    Roff,
+}
+
+pub async fn run(command: Command, provided_token: Option<ProvidedToken>) -> Result<()> {
+    match command {
+        Command::Next => connect(provided_token)
+            .await?
+            .skip_track(SkipDirection::Next)
+            .await
+            .map_err(|e| eyre!("{}", e))?,
+        Command::Pause => connect(provided_token)
+            .await?
+            .pause()
+            .await
+            .map_err(|e| eyre!("{}", e))?,
+        Command::Now { json } => {
+            let player = connect(provided_token)
+                .await?
+                .get_current_playback()
+                .await
+                .map_err(|e| eyre!("{}", e))?;
+            print_now_playing(&player, json)?;
+        }
+        // キー割り当ての書き出しは認証不要（config.json で割り当て直したキーは反映する）
+        Command::Keys { format } => {
+            let config = Config::load().map_err(|e| eyre!("{}", e))?;
+            keymap::init(&config.keybindings);
+            for notice in rs_pod::notices::take() {
+                eprintln!("{}", notice);
+            }
+            let output = match format {
+                KeysFormat::Markdown => keymap::to_markdown(),
+                KeysFormat::Roff => keymap::to_roff(),
+            };
+            print!("{}", output);
+        }
+    }
+    Ok(())
+}
+
+async fn connect(provided_token: Option<ProvidedToken>) -> Result<SpotifyClient> {
+    let config = Config::load().map_err(|e| eyre!("{}", e))?;
+    SpotifyClient::connect(&config, provided_token)
+        .await
+        .map_err(|e| eyre!("{}", e))
+}
+
+fn print_now_playing(player: &SpotifyPlayer, json: bool) -> Result<()> {
+    let progress_ms = player.progress_ms.unwrap_or(0);
+
+    if json {
+        println!("{}", serde_json::to_string(&player.state_json())?);
+        return Ok(());
+    }
+
+    match &player.item {
+        Some(track) => println!(
+            "{} {} — {} ({} / {})",
+            if player.is_playing { "▶" } else { "⏸" },
+            track.name,
+            track.artist_names(),
+            format_time(progress_ms),
+            format_time(track.duration_ms)
+        ),
+        None => println!("No track playing"),
+    }
+    Ok(())
+}
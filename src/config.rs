@@ -0,0 +1,203 @@
+use serde::Deserialize;
+use std::{collections::HashMap, fs, path::PathBuf, time::Duration};
+
+const CONFIG_FILE: &str = "config.json";
+/// これより短いポーリング間隔は API を叩きすぎるので切り上げる
+const MIN_POLL_INTERVAL_MS: u64 = 200;
+
+/// ~/.config/rs-pod/config.json の設定（未指定の項目はデフォルト値）
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct Config {
+    /// ポッドキャストの再生位置をローカルに保存して再開を提案する
+    pub resume_episodes: bool,
+    /// 終了時に再生していた曲を、この時間（時間単位）以内に起動したら続きから再生できるようにする（0 で無効）
+    pub resume_last_played_hours: u64,
+    /// User-Agent を上書きする（社内プロキシのフィルタ対策など）
+    pub user_agent: Option<String>,
+    /// 端末からフォーカスが外れている間はポーリングを間引く（フォーカスイベント対応端末のみ）
+    pub slow_poll_when_unfocused: bool,
+    /// 再生中の曲を書き出すファイル（polybar / waybar 用。例: "~/.cache/rs-pod/now.txt"）
+    pub now_playing_file: Option<PathBuf>,
+    /// 書き出す文字列（{artist} {title} {state} を置き換える）
+    pub now_playing_format: String,
+    /// 曲のリストを 番号・曲名・アーティスト・長さ の列で表示する（false なら「曲名 - アーティスト」）
+    pub track_columns: bool,
+    /// シャッフルやリピートの表示に絵文字を使わない（フォントに絵文字が無い端末向け）
+    /// UTF-8 でないロケールでは指定しなくても ASCII になる
+    pub ascii_icons: bool,
+    /// ブラウザでの認可を待つ秒数（過ぎるとエラーで終了する）
+    pub auth_timeout_secs: u64,
+    /// rs-pod が必要とするものに加えて要求するスコープ（変えると次回起動時に認可し直す）
+    pub extra_scopes: Vec<String>,
+    /// 再生状態を JSON 行で配信するソケット（Unix ではこのパスのドメインソケット）
+    pub state_socket: Option<PathBuf>,
+    /// Unix 以外で state_socket の代わりに待ち受ける 127.0.0.1 のポート
+    pub state_socket_port: u16,
+    /// プレイリストに曲を追加する前に、すでに入っていないか確認する
+    pub check_duplicates: bool,
+    /// 再生状態を取得する間隔（ミリ秒、最小 200）。短くすると 429 で待たされやすくなる
+    pub poll_interval_ms: u64,
+    /// 再生画面にジャケット画像を出す（kitty / sixel / iTerm2 に対応していない端末では文字で描く）
+    pub album_art: bool,
+    /// プレイリスト一覧の各行の左にカバー画像を小さく出す（行の高さが 2 行になる）
+    pub playlist_thumbnails: bool,
+    /// 表示の色
+    pub theme: Theme,
+    /// プレイリスト一覧の Enter での再生のしかた（"in_order" か "shuffle"）
+    pub playlist_play_mode: PlayMode,
+    /// API への接続を待つ秒数（0 で無制限）
+    pub connect_timeout_secs: u64,
+    /// レスポンスの読み込みが止まってから諦めるまでの秒数（0 で無制限）
+    pub read_timeout_secs: u64,
+    /// API と認可に使うプロキシ（例: "http://proxy.example.com:8080"）。無ければ HTTPS_PROXY などの環境変数に従う
+    pub proxy_url: Option<String>,
+    /// スリープタイマーの最後の 1 分で音量を少しずつ下げる（止めた後は元の音量に戻す）
+    pub sleep_fade: bool,
+    /// スリープタイマーで一時停止した後の動作（"pause" / "quit" / "clock"）
+    pub sleep_action: SleepAction,
+    /// 検索結果から 1 曲だけ再生したとき、終わったら結果の次の曲を続けて再生する
+    pub auto_advance: bool,
+    /// 再生画面の曲名の上にイコライザー風の飾りを出す（音は取れないので再生位置から作った動き）
+    pub visualizer: bool,
+    /// キーの割り当てを変える（操作名 → キーの一覧。例: {"toggle_playback": ["p", "ctrl-p"]}）
+    pub keybindings: HashMap<String, Vec<String>>,
+}
+
+/// スリープタイマーで一時停止した後の動作
+#[derive(Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SleepAction {
+    /// 一時停止するだけ
+    #[default]
+    Pause,
+    /// rs-pod を終了する
+    Quit,
+    /// 暗い時計の画面にする（どのキーでも戻る）
+    Clock,
+}
+
+/// プレイリストを再生するときの順番
+#[derive(Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PlayMode {
+    /// 1 曲目から順に
+    #[default]
+    InOrder,
+    /// シャッフルを有効にして、ランダムな曲から
+    Shuffle,
+}
+
+/// 表示の色（"#rrggbb" か "yellow" などの色名）
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct Theme {
+    /// プログレスバーの色
+    pub progress: String,
+    /// 曲の残りがこの秒数を切ったらプログレスバーを progress_ending の色にする（0 で無効）
+    pub progress_ending_secs: u64,
+    pub progress_ending: String,
+    /// 使う色数（"auto" なら COLORTERM / TERM から判断、"truecolor" / "ansi256" / "ansi16" で決め打ち）
+    pub palette: Palette,
+}
+
+/// 端末の色数の指定
+#[derive(Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Palette {
+    #[default]
+    Auto,
+    Truecolor,
+    Ansi256,
+    Ansi16,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            progress: "#0AE164".to_string(),
+            progress_ending_secs: 30,
+            progress_ending: "#FFBF00".to_string(),
+            palette: Palette::Auto,
+        }
+    }
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            resume_episodes: true,
+            resume_last_played_hours: 12,
+            user_agent: None,
+            slow_poll_when_unfocused: false,
+            now_playing_file: None,
+            now_playing_format: "{state} {artist} - {title}".to_string(),
+            track_columns: true,
+            poll_interval_ms: 1000,
+            check_duplicates: true,
+            ascii_icons: false,
+            auth_timeout_secs: 120,
+            extra_scopes: Vec::new(),
+            state_socket: None,
+            state_socket_port: 47480,
+            album_art: true,
+            playlist_thumbnails: true,
+            theme: Theme::default(),
+            playlist_play_mode: PlayMode::InOrder,
+            connect_timeout_secs: 10,
+            read_timeout_secs: 30,
+            proxy_url: None,
+            sleep_fade: true,
+            sleep_action: SleepAction::Pause,
+            auto_advance: false,
+            visualizer: false,
+            keybindings: HashMap::new(),
+        }
+    }
+}
+
+impl Config {
+    pub fn load() -> Result<Self, Box<dyn std::error::Error>> {
+        let path = config_dir().join(CONFIG_FILE);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let json = fs::read_to_string(&path)?;
+        let config = serde_json::from_str(&json)
+            .map_err(|e| format!("Invalid config {}: {}", path.display(), e))?;
+        Ok(config)
+    }
+
+    pub fn auth_timeout(&self) -> Duration {
+        Duration::from_secs(self.auth_timeout_secs)
+    }
+
+    pub fn connect_timeout(&self) -> Option<Duration> {
+        (self.connect_timeout_secs > 0).then(|| Duration::from_secs(self.connect_timeout_secs))
+    }
+
+    pub fn read_timeout(&self) -> Option<Duration> {
+        (self.read_timeout_secs > 0).then(|| Duration::from_secs(self.read_timeout_secs))
+    }
+
+    /// 再生状態のポーリング間隔（下限で切り上げ済み）
+    pub fn poll_interval(&self) -> Duration {
+        Duration::from_millis(self.poll_interval_ms.max(MIN_POLL_INTERVAL_MS))
+    }
+}
+
+/// 設定で指定されたパスの先頭の "~" をホームディレクトリに展開する
+pub fn expand_home(path: PathBuf) -> PathBuf {
+    match (path.strip_prefix("~"), dirs::home_dir()) {
+        (Ok(rest), Some(home)) => home.join(rest),
+        _ => path,
+    }
+}
+
+/// 設定や状態ファイルを置くディレクトリ
+pub fn config_dir() -> PathBuf {
+    dirs::config_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("rs-pod")
+}
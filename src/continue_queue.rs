@@ -0,0 +1,64 @@
+use crate::api::spotify::{SpotifyPlayer, Track};
+use std::collections::VecDeque;
+
+/// 単曲で再生したとき、曲が終わったら続けて流す曲（検索結果の続きなど）
+///
+/// 単曲の再生はコンテキストが無いので最後まで聴くと止まってしまう。
+/// 前回と今回の再生状態を比べて止まったことを見つけ、次の曲を返す。
+#[derive(Debug, Default)]
+pub struct ContinueQueue {
+    /// 今流している曲の URI
+    current: Option<String>,
+    /// current が再生中として返ってきたか（再生直後は前の曲が返ってくることがある）
+    confirmed: bool,
+    upcoming: VecDeque<Track>,
+}
+
+impl ContinueQueue {
+    pub fn start(&mut self, current: String, upcoming: impl IntoIterator<Item = Track>) {
+        self.current = Some(current);
+        self.confirmed = false;
+        self.upcoming = upcoming.into_iter().collect();
+    }
+
+    pub fn clear(&mut self) {
+        self.current = None;
+        self.confirmed = false;
+        self.upcoming.clear();
+    }
+
+    /// 流していた曲が最後まで再生されて止まっていれば次の曲を返す
+    ///
+    /// end_margin_ms は前回のポーリングで曲の終わりとみなす残り時間（ポーリングの間隔より長く）。
+    pub fn next_after(&mut self, previous: &SpotifyPlayer, player: &SpotifyPlayer, end_margin_ms: i64) -> Option<Track> {
+        let current = self.current.as_deref()?;
+        let other_item = player.item.as_ref().is_some_and(|track| track.uri != current);
+        if !self.confirmed {
+            self.confirmed = !other_item && player.item.is_some();
+            return None;
+        }
+        // コンテキストや別の曲を再生し始めたら続きは流さない
+        if player.context.is_some() || other_item {
+            self.clear();
+            return None;
+        }
+
+        let track = previous.item.as_ref().filter(|track| track.uri == current)?;
+        let previous_progress = previous.progress_ms.unwrap_or(0);
+        let near_end = previous.is_playing && track.duration_ms - previous_progress <= end_margin_ms;
+        // 終わると先頭に戻って止まる（item が null になることもある）。途中で一時停止しただけなら位置は戻らない
+        let stopped = !player.is_playing
+            && (player.item.is_none() || player.progress_ms.unwrap_or(0) < previous_progress);
+        if !(near_end && stopped) {
+            return None;
+        }
+
+        let Some(next) = self.upcoming.pop_front() else {
+            self.clear();
+            return None;
+        };
+        self.current = Some(next.uri.clone());
+        self.confirmed = false;
+        Some(next)
+    }
+}
@@ -0,0 +1,139 @@
+use crate::config::config_dir;
+use std::{
+    collections::hash_map::DefaultHasher,
+    fs::{self, File},
+    hash::{Hash, Hasher},
+    io,
+    path::PathBuf,
+    time::SystemTime,
+};
+
+const CACHE_DIR: &str = "image_cache";
+const MAX_CACHE_BYTES: u64 = 50 * 1024 * 1024;
+
+/// 画像 URL のハッシュをキーにしたディスクキャッシュ（最終アクセス順で LRU 削除）
+#[derive(Debug, Clone)]
+pub struct ImageCache {
+    dir: PathBuf,
+    max_bytes: u64,
+}
+
+impl Default for ImageCache {
+    fn default() -> Self {
+        Self::new(config_dir().join(CACHE_DIR), MAX_CACHE_BYTES)
+    }
+}
+
+impl ImageCache {
+    /// dir に最大 max_bytes まで保存するキャッシュ
+    pub fn new(dir: PathBuf, max_bytes: u64) -> Self {
+        Self { dir, max_bytes }
+    }
+
+    fn path_for(&self, url: &str) -> PathBuf {
+        let mut hasher = DefaultHasher::new();
+        url.hash(&mut hasher);
+        self.dir.join(format!("{:016x}", hasher.finish()))
+    }
+
+    /// キャッシュ済みなら画像のバイト列を返す
+    pub fn get(&self, url: &str) -> Option<Vec<u8>> {
+        let path = self.path_for(url);
+        let bytes = fs::read(&path).ok()?;
+        // 更新時刻を最終アクセス時刻として使う
+        if let Ok(file) = File::options().write(true).open(&path) {
+            let _ = file.set_modified(SystemTime::now());
+        }
+        Some(bytes)
+    }
+
+    pub fn put(&self, url: &str, bytes: &[u8]) -> io::Result<()> {
+        fs::create_dir_all(&self.dir)?;
+        fs::write(self.path_for(url), bytes)?;
+        self.evict()
+    }
+
+    /// 上限を超えた分を古いものから削除する
+    fn evict(&self) -> io::Result<()> {
+        let mut entries = Vec::new();
+        let mut total = 0;
+        for entry in fs::read_dir(&self.dir)? {
+            let entry = entry?;
+            let metadata = entry.metadata()?;
+            if !metadata.is_file() {
+                continue;
+            }
+            total += metadata.len();
+            entries.push((metadata.modified()?, metadata.len(), entry.path()));
+        }
+
+        entries.sort_by_key(|(modified, _, _)| *modified);
+        for (_, len, path) in entries {
+            if total <= self.max_bytes {
+                break;
+            }
+            fs::remove_file(path)?;
+            total -= len;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ImageCache;
+    use std::{
+        env,
+        fs::{self, File},
+        path::PathBuf,
+        process,
+        time::{Duration, SystemTime},
+    };
+
+    /// テストごとの空のディレクトリ
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("rs-pod-image-cache-{}-{}", name, process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    /// 最終アクセス時刻を secs 秒前にする
+    fn age(cache: &ImageCache, url: &str, secs: u64) {
+        let file = File::options().write(true).open(cache.path_for(url)).unwrap();
+        file.set_modified(SystemTime::now() - Duration::from_secs(secs)).unwrap();
+    }
+
+    #[test]
+    fn get_after_put_returns_bytes() {
+        let dir = temp_dir("hit");
+        let cache = ImageCache::new(dir.clone(), 1024);
+        assert_eq!(cache.get("https://i.scdn.co/image/a"), None);
+        cache.put("https://i.scdn.co/image/a", b"cover").unwrap();
+        assert_eq!(cache.get("https://i.scdn.co/image/a").as_deref(), Some(&b"cover"[..]));
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn over_max_bytes_evicts_oldest_first() {
+        let dir = temp_dir("evict");
+        let cache = ImageCache::new(dir.clone(), 25);
+        cache.put("a", &[0; 10]).unwrap();
+        cache.put("b", &[0; 10]).unwrap();
+        age(&cache, "a", 20);
+        age(&cache, "b", 10);
+
+        cache.put("c", &[0; 10]).unwrap();
+        assert_eq!(cache.get("a"), None);
+        assert!(cache.get("b").is_some());
+        assert!(cache.get("c").is_some());
+
+        // 読むと新しくなるので、次は読んでいないものから消える
+        age(&cache, "b", 20);
+        age(&cache, "c", 10);
+        assert!(cache.get("b").is_some());
+        cache.put("d", &[0; 10]).unwrap();
+        assert_eq!(cache.get("c"), None);
+        assert!(cache.get("b").is_some());
+        let _ = fs::remove_dir_all(dir);
+    }
+}
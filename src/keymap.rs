@@ -0,0 +1,533 @@
+use crate::action::Action;
+use crate::app::Page;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::{borrow::Cow, collections::HashMap, sync::OnceLock};
+
+/// config.json の keybindings で割り当て直したキー（操作ごと）
+static OVERRIDES: OnceLock<HashMap<Action, Vec<KeyAction>>> = OnceLock::new();
+
+/// キー割り当て（ヘルプの表示と、キーから操作への変換の両方に使う）
+#[derive(Debug, Clone, Copy)]
+pub struct KeyBinding {
+    /// ヘルプに表示するキーの表記
+    pub keys: &'static str,
+    pub description: &'static str,
+    /// 実際に受け付けるキーと操作（空ならヘルプに表示するだけ）
+    pub actions: &'static [KeyAction],
+}
+
+/// 1 つのキーと、それで実行する操作
+#[derive(Debug, Clone, Copy)]
+pub struct KeyAction {
+    pub code: KeyCode,
+    /// Ctrl を押しているときだけ有効か（そうでなければ修飾キーは見ない）
+    pub ctrl: bool,
+    pub action: Action,
+}
+
+impl KeyAction {
+    pub fn matches(&self, key_event: KeyEvent) -> bool {
+        self.code == key_event.code && (!self.ctrl || key_event.modifiers.contains(KeyModifiers::CONTROL))
+    }
+
+    /// ヘルプ用のキーの表記（表の keys と同じ書き方）
+    fn label(&self) -> String {
+        let name = match self.code {
+            KeyCode::Char(' ') => "Space".to_string(),
+            KeyCode::Char(c) if self.ctrl => c.to_ascii_uppercase().to_string(),
+            KeyCode::Char(c) => c.to_string(),
+            KeyCode::Enter => "Enter".to_string(),
+            KeyCode::Esc => "Esc".to_string(),
+            KeyCode::Tab => "Tab".to_string(),
+            KeyCode::Backspace => "Backspace".to_string(),
+            KeyCode::Up => "↑".to_string(),
+            KeyCode::Down => "↓".to_string(),
+            KeyCode::Left => "←".to_string(),
+            KeyCode::Right => "→".to_string(),
+            KeyCode::PageUp => "PgUp".to_string(),
+            KeyCode::PageDown => "PgDn".to_string(),
+            KeyCode::Home => "Home".to_string(),
+            KeyCode::End => "End".to_string(),
+            KeyCode::F(n) => format!("F{}", n),
+            code => format!("{:?}", code),
+        };
+        if self.ctrl {
+            format!("Ctrl-{}", name)
+        } else {
+            name
+        }
+    }
+}
+
+impl KeyBinding {
+    /// 実際に受け付けるキー（config.json で割り当て直した操作はそのキーに置き換える）
+    pub fn key_actions(&self) -> Vec<KeyAction> {
+        match OVERRIDES.get() {
+            Some(overrides) => apply_overrides(self.actions, overrides),
+            None => self.actions.to_vec(),
+        }
+    }
+
+    /// ヘルプに表示するキーの表記（割り当て直した行は実際のキーから作る）
+    pub fn label(&self) -> Cow<'static, str> {
+        let overridden = OVERRIDES
+            .get()
+            .is_some_and(|overrides| self.actions.iter().any(|key| overrides.contains_key(&key.action)));
+        if !overridden {
+            return Cow::Borrowed(self.keys);
+        }
+        let labels: Vec<String> = self.key_actions().iter().map(KeyAction::label).collect();
+        if labels.is_empty() {
+            Cow::Borrowed("(unbound)")
+        } else {
+            Cow::Owned(labels.join(" / "))
+        }
+    }
+}
+
+/// 起動時に config.json の keybindings で割り当てを変える（2 回目以降は無視）
+///
+/// 知らない操作名やキーはステータス行で知らせて、その項目だけ無視する。
+pub fn init(keybindings: &HashMap<String, Vec<String>>) {
+    let (overrides, errors) = parse_overrides(keybindings);
+    for error in errors {
+        crate::notices::push(error);
+    }
+    let _ = OVERRIDES.set(overrides);
+}
+
+fn parse_overrides(keybindings: &HashMap<String, Vec<String>>) -> (HashMap<Action, Vec<KeyAction>>, Vec<String>) {
+    let mut overrides = HashMap::new();
+    let mut errors = Vec::new();
+    for (name, specs) in keybindings {
+        // 数字キーでのシークのように値を持つ操作は割り当て直せない
+        let Ok(action) = serde_json::from_value::<Action>(name.as_str().into()) else {
+            errors.push(format!("Unknown action in keybindings: {}", name));
+            continue;
+        };
+        let mut keys = Vec::new();
+        for spec in specs {
+            match parse_key(spec) {
+                Some((code, ctrl)) => keys.push(KeyAction { code, ctrl, action }),
+                None => errors.push(format!("Unknown key \"{}\" for {} in keybindings", spec, name)),
+            }
+        }
+        overrides.insert(action, keys);
+    }
+    (overrides, errors)
+}
+
+/// "p" / "ctrl-p" / "space" / "enter" / "f5" などのキー名（大文字小文字は名前だけ区別しない）
+fn parse_key(spec: &str) -> Option<(KeyCode, bool)> {
+    let (name, ctrl) = match spec.get(..5) {
+        Some(prefix) if prefix.eq_ignore_ascii_case("ctrl-") => (&spec[5..], true),
+        _ => (spec, false),
+    };
+    let mut chars = name.chars();
+    if let (Some(c), None) = (chars.next(), chars.next()) {
+        // Ctrl 付きの文字は端末から小文字で届く
+        let c = if ctrl { c.to_ascii_lowercase() } else { c };
+        return Some((KeyCode::Char(c), ctrl));
+    }
+    let code = match name.to_ascii_lowercase().as_str() {
+        "space" => KeyCode::Char(' '),
+        "enter" => KeyCode::Enter,
+        "esc" => KeyCode::Esc,
+        "tab" => KeyCode::Tab,
+        "backspace" => KeyCode::Backspace,
+        "up" => KeyCode::Up,
+        "down" => KeyCode::Down,
+        "left" => KeyCode::Left,
+        "right" => KeyCode::Right,
+        "pageup" => KeyCode::PageUp,
+        "pagedown" => KeyCode::PageDown,
+        "home" => KeyCode::Home,
+        "end" => KeyCode::End,
+        f => KeyCode::F(f.strip_prefix('f')?.parse().ok()?),
+    };
+    Some((code, ctrl))
+}
+
+/// 行のキーのうち、割り当て直した操作のものを設定のキーに置き換える
+fn apply_overrides(actions: &[KeyAction], overrides: &HashMap<Action, Vec<KeyAction>>) -> Vec<KeyAction> {
+    let mut keys: Vec<KeyAction> = Vec::new();
+    for (i, key) in actions.iter().enumerate() {
+        match overrides.get(&key.action) {
+            // 同じ操作のキーが行に複数あっても設定のキーは一度だけ入れる
+            Some(replaced) => {
+                if !actions[..i].iter().any(|other| other.action == key.action) {
+                    keys.extend(replaced);
+                }
+            }
+            None => keys.push(*key),
+        }
+    }
+    keys
+}
+
+const fn bind(keys: &'static str, description: &'static str, actions: &'static [KeyAction]) -> KeyBinding {
+    KeyBinding {
+        keys,
+        description,
+        actions,
+    }
+}
+
+const fn key(code: KeyCode, action: Action) -> KeyAction {
+    KeyAction {
+        code,
+        ctrl: false,
+        action,
+    }
+}
+
+const fn chr(c: char, action: Action) -> KeyAction {
+    key(KeyCode::Char(c), action)
+}
+
+const fn ctrl(c: char, action: Action) -> KeyAction {
+    KeyAction {
+        code: KeyCode::Char(c),
+        ctrl: true,
+        action,
+    }
+}
+
+const SEEK: &[KeyAction] = &[
+    chr('0', Action::SeekTenths(0)),
+    chr('1', Action::SeekTenths(1)),
+    chr('2', Action::SeekTenths(2)),
+    chr('3', Action::SeekTenths(3)),
+    chr('4', Action::SeekTenths(4)),
+    chr('5', Action::SeekTenths(5)),
+    chr('6', Action::SeekTenths(6)),
+    chr('7', Action::SeekTenths(7)),
+    chr('8', Action::SeekTenths(8)),
+    chr('9', Action::SeekTenths(9)),
+];
+
+/// リストでの移動（各ページの表に同じキーを載せる）
+const UP: &[KeyAction] = &[key(KeyCode::Up, Action::MoveUp), chr('k', Action::MoveUp)];
+const DOWN: &[KeyAction] = &[key(KeyCode::Down, Action::MoveDown), chr('j', Action::MoveDown)];
+const PAGE: &[KeyAction] = &[key(KeyCode::PageUp, Action::PageUp), key(KeyCode::PageDown, Action::PageDown)];
+const ENDS: &[KeyAction] = &[key(KeyCode::Home, Action::First), key(KeyCode::End, Action::Last)];
+const BACK: &[KeyAction] = &[
+    key(KeyCode::Esc, Action::Back),
+    chr('h', Action::Back),
+    key(KeyCode::Left, Action::Back),
+];
+
+/// ページ以外のリスト（プレイリストの選択など）で使う移動キー
+pub const LIST: &[KeyBinding] = &[
+    bind("↑ / k", "Select previous", UP),
+    bind("↓ / j", "Select next", DOWN),
+    bind("PgUp / PgDn", "Move by a page", PAGE),
+    bind("Home / End", "Jump to first / last", ENDS),
+];
+
+/// どのページでも使えるキー
+pub const GLOBAL: &[KeyBinding] = &[
+    bind("[", "Go back", &[chr('[', Action::HistoryBack)]),
+    bind("]", "Go forward", &[chr(']', Action::HistoryForward)]),
+    bind(
+        "g / Tab",
+        "Jump to Now Playing",
+        &[chr('g', Action::OpenNowPlaying), key(KeyCode::Tab, Action::OpenNowPlaying)],
+    ),
+    // 回数は App が数字キーを貯めて、次の移動に使う
+    bind("<count> j / k", "Move that many rows in a list (e.g. 5j)", &[]),
+    bind("R", "Refresh the current page", &[chr('R', Action::Refresh)]),
+    bind("?", "Show this help", &[chr('?', Action::ShowHelp)]),
+    bind("q / Ctrl-C", "Quit", &[chr('q', Action::Quit), ctrl('c', Action::Quit)]),
+];
+
+pub const PLAYLIST_LIST: &[KeyBinding] = &[
+    bind("↑ / k", "Select previous playlist", UP),
+    bind("↓ / j", "Select next playlist", DOWN),
+    bind("PgUp / PgDn", "Move by a page", PAGE),
+    bind("Home / End", "Jump to first / last", ENDS),
+    bind("Enter", "Play playlist", &[key(KeyCode::Enter, Action::Select)]),
+    bind(
+        "l / →",
+        "Open playlist tracks",
+        &[chr('l', Action::Open), key(KeyCode::Right, Action::Open)],
+    ),
+    bind("e", "Preview and enqueue playlist", &[chr('e', Action::Enqueue)]),
+    bind("n", "Browse new releases", &[chr('n', Action::OpenNewReleases)]),
+    bind("s", "Search for tracks", &[chr('s', Action::OpenSearch)]),
+    bind("r", "Retry loading playlists", &[chr('r', Action::RetryPlaylists)]),
+    bind("/", "Filter playlists (Esc clears)", &[chr('/', Action::Filter)]),
+    bind("c", "Create a new playlist", &[chr('c', Action::CreatePlaylist)]),
+];
+
+pub const PLAYLIST_TRACKS: &[KeyBinding] = &[
+    bind("↑ / k", "Select previous track", UP),
+    bind("↓ / j", "Select next track", DOWN),
+    bind("PgUp / PgDn", "Move by a page", PAGE),
+    bind("Home / End", "Jump to first / last", ENDS),
+    bind("Enter", "Play playlist from this track", &[key(KeyCode::Enter, Action::Select)]),
+    bind("e", "Preview and enqueue playlist", &[chr('e', Action::Enqueue)]),
+    bind("f", "Follow / unfollow playlist", &[chr('f', Action::ToggleFollow)]),
+    bind("d", "Remove track from playlist", &[chr('d', Action::RemoveTrack)]),
+    bind(
+        "K / J",
+        "Move track up / down in the playlist",
+        &[chr('K', Action::MoveTrackUp), chr('J', Action::MoveTrackDown)],
+    ),
+    bind("Esc / h / ←", "Go back", BACK),
+];
+
+pub const NOW_PLAYING: &[KeyBinding] = &[
+    bind("←", "Previous track", &[key(KeyCode::Left, Action::Previous)]),
+    bind("→", "Next track", &[key(KeyCode::Right, Action::Next)]),
+    bind("Space", "Play / pause", &[chr(' ', Action::TogglePlayback)]),
+    bind(
+        "+ / -",
+        "Volume up / down",
+        // Shift を押さずに打てるように = でも上げる
+        &[chr('+', Action::VolumeUp), chr('=', Action::VolumeUp), chr('-', Action::VolumeDown)],
+    ),
+    bind("0-9", "Seek to 0%-90% of the track", SEEK),
+    bind("Backspace", "Restart the track", &[key(KeyCode::Backspace, Action::Restart)]),
+    bind("r", "Resume the saved episode or last session", &[chr('r', Action::Resume)]),
+    bind("a", "Show all artists", &[chr('a', Action::ShowArtists)]),
+    bind("i", "Show track details", &[chr('i', Action::ShowTrackInfo)]),
+    bind("y", "Copy the track's share link", &[chr('y', Action::CopyLink)]),
+    bind("o", "Open the track in the browser", &[chr('o', Action::OpenInBrowser)]),
+    bind("z", "Set / cancel the sleep timer", &[chr('z', Action::SleepTimer)]),
+    bind("A", "Add the track to a playlist", &[chr('A', Action::AddToPlaylist)]),
+    bind("d", "Choose a playback device", &[chr('d', Action::OpenDevices)]),
+    bind("p", "Open playlists", &[chr('p', Action::OpenPlaylists)]),
+    bind("Esc", "Go back", &[key(KeyCode::Esc, Action::Back)]),
+];
+
+pub const DEVICES: &[KeyBinding] = &[
+    bind("↑ / k", "Select previous device", UP),
+    bind("↓ / j", "Select next device", DOWN),
+    bind("PgUp / PgDn", "Move by a page", PAGE),
+    bind("Home / End", "Jump to first / last", ENDS),
+    bind("Enter", "Play on this device", &[key(KeyCode::Enter, Action::Select)]),
+    bind("r", "Reload devices", &[chr('r', Action::Reload)]),
+    bind("Esc", "Go back", &[key(KeyCode::Esc, Action::Back)]),
+];
+
+pub const QUEUE_PREVIEW: &[KeyBinding] = &[
+    bind("↑ / k", "Select previous track", UP),
+    bind("↓ / j", "Select next track", DOWN),
+    bind("PgUp / PgDn", "Move by a page", PAGE),
+    bind("Home / End", "Jump to first / last", ENDS),
+    bind("K", "Move track up", &[chr('K', Action::MoveTrackUp)]),
+    bind("J", "Move track down", &[chr('J', Action::MoveTrackDown)]),
+    bind("Space", "Include / exclude track", &[chr(' ', Action::ToggleInclude)]),
+    bind("Enter", "Add to queue in this order", &[key(KeyCode::Enter, Action::Select)]),
+    bind("Esc", "Go back / cancel", &[key(KeyCode::Esc, Action::Back)]),
+];
+
+pub const NEW_RELEASES: &[KeyBinding] = &[
+    bind("↑ / k", "Select previous album", UP),
+    bind("↓ / j", "Select next album", DOWN),
+    bind("PgUp / PgDn", "Move by a page", PAGE),
+    bind("Home / End", "Jump to first / last", ENDS),
+    bind(
+        "Enter / l / →",
+        "Open album tracks",
+        &[
+            key(KeyCode::Enter, Action::Open),
+            chr('l', Action::Open),
+            key(KeyCode::Right, Action::Open),
+        ],
+    ),
+    bind("Esc / h / ←", "Go back", BACK),
+];
+
+pub const ALBUM_TRACKS: &[KeyBinding] = &[
+    bind("↑ / k", "Select previous track", UP),
+    bind("↓ / j", "Select next track", DOWN),
+    bind("PgUp / PgDn", "Move by a page", PAGE),
+    bind("Home / End", "Jump to first / last", ENDS),
+    bind("Enter", "Play album from this track", &[key(KeyCode::Enter, Action::Select)]),
+    bind("s", "Save / remove album from your library", &[chr('s', Action::ToggleSave)]),
+    bind("Esc / h / ←", "Go back", BACK),
+];
+
+/// 文字は検索語の入力に使うので、文字キーの操作は Ctrl 付きだけ
+pub const SEARCH: &[KeyBinding] = &[
+    bind("Type", "Search as you type", &[]),
+    bind(
+        "↑ / ↓",
+        "Select previous / next result",
+        &[key(KeyCode::Up, Action::MoveUp), key(KeyCode::Down, Action::MoveDown)],
+    ),
+    bind("PgUp / PgDn", "Move by a page", PAGE),
+    bind("Home / End", "Jump to first / last", ENDS),
+    bind("Enter", "Play track", &[key(KeyCode::Enter, Action::Select)]),
+    bind("Ctrl-E", "Add track to the queue", &[ctrl('e', Action::Enqueue)]),
+    bind("Esc", "Go back", &[key(KeyCode::Esc, Action::Back)]),
+];
+
+/// 書き出し用の見出しとキー割り当て（ヘルプと同じデータ）
+pub const SECTIONS: &[(&str, &[KeyBinding])] = &[
+    ("Global", GLOBAL),
+    ("Playlist list", PLAYLIST_LIST),
+    ("Playlist tracks", PLAYLIST_TRACKS),
+    ("Now playing", NOW_PLAYING),
+    ("Devices", DEVICES),
+    ("Queue preview", QUEUE_PREVIEW),
+    ("New releases", NEW_RELEASES),
+    ("Album tracks", ALBUM_TRACKS),
+    ("Search", SEARCH),
+];
+
+/// チートシート用に Markdown の表として書き出す
+pub fn to_markdown() -> String {
+    let mut out = String::from("# rs-pod keybindings\n");
+    for (title, bindings) in SECTIONS {
+        out.push_str(&format!("\n## {}\n\n| Key | Action |\n| --- | --- |\n", title));
+        for binding in *bindings {
+            // 表の区切りと衝突しないようにエスケープ
+            let keys = binding.label().replace('|', "\\|");
+            out.push_str(&format!("| `{}` | {} |\n", keys, binding.description));
+        }
+    }
+    out
+}
+
+/// man ページ用に roff で書き出す
+pub fn to_roff() -> String {
+    let mut out = String::from(".TH RS-POD 1\n.SH NAME\nrs-pod \\- Spotify player for the terminal\n.SH KEYBINDINGS\n");
+    for (title, bindings) in SECTIONS {
+        out.push_str(&format!(".SS {}\n", roff_escape(title)));
+        for binding in *bindings {
+            out.push_str(&format!(
+                ".TP\n.B \"{}\"\n{}\n",
+                roff_escape(&binding.label()),
+                roff_escape(binding.description)
+            ));
+        }
+    }
+    out
+}
+
+fn roff_escape(s: &str) -> String {
+    let escaped = s.replace('\\', "\\e").replace('-', "\\-");
+    // 行頭の . と ' は制御行として解釈されてしまう
+    if escaped.starts_with(['.', '\'']) {
+        format!("\\&{}", escaped)
+    } else {
+        escaped
+    }
+}
+
+/// ページ固有のキー割り当て
+pub fn bindings_for(page: Page) -> &'static [KeyBinding] {
+    match page {
+        Page::PlaylistList => PLAYLIST_LIST,
+        Page::PlaylistTracks => PLAYLIST_TRACKS,
+        Page::NowPlaying => NOW_PLAYING,
+        Page::Devices => DEVICES,
+        Page::QueuePreview => QUEUE_PREVIEW,
+        Page::NewReleases => NEW_RELEASES,
+        Page::AlbumTracks => ALBUM_TRACKS,
+        Page::Search => SEARCH,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{apply_overrides, bindings_for, parse_key, parse_overrides, KeyAction, GLOBAL, NOW_PLAYING, UP};
+    use crate::action::Action;
+    use crate::app::Page;
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+    use std::collections::HashMap;
+
+    const PAGES: [Page; 8] = [
+        Page::PlaylistList,
+        Page::PlaylistTracks,
+        Page::NowPlaying,
+        Page::Devices,
+        Page::QueuePreview,
+        Page::NewReleases,
+        Page::AlbumTracks,
+        Page::Search,
+    ];
+
+    #[test]
+    fn no_key_is_shadowed() {
+        for page in PAGES {
+            let keys: Vec<_> = GLOBAL
+                .iter()
+                .chain(bindings_for(page))
+                .flat_map(|binding| binding.actions)
+                .collect();
+            for (i, key) in keys.iter().enumerate() {
+                let earlier = keys[..i].iter().find(|other| other.code == key.code && other.ctrl == key.ctrl);
+                assert!(
+                    earlier.is_none_or(|other| other.action == key.action),
+                    "{:?}: {:?} is bound to both {:?} and {:?}",
+                    page,
+                    key.code,
+                    earlier.map(|other| other.action),
+                    key.action
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn every_listed_key_maps_to_its_action() {
+        for page in PAGES {
+            for key in GLOBAL.iter().chain(bindings_for(page)).flat_map(|binding| binding.actions) {
+                let modifiers = if key.ctrl { KeyModifiers::CONTROL } else { KeyModifiers::NONE };
+                let typed = page == Page::Search && matches!(key.code, KeyCode::Char(_)) && !key.ctrl;
+                let expected = (!typed).then_some(key.action);
+                let action = Action::from_key(page, KeyEvent::new(key.code, modifiers));
+                assert_eq!(action, expected, "{:?} {:?}", page, key.code);
+            }
+        }
+    }
+
+    #[test]
+    fn parses_key_names() {
+        assert_eq!(parse_key("p"), Some((KeyCode::Char('p'), false)));
+        assert_eq!(parse_key("P"), Some((KeyCode::Char('P'), false)));
+        assert_eq!(parse_key("Ctrl-P"), Some((KeyCode::Char('p'), true)));
+        assert_eq!(parse_key("space"), Some((KeyCode::Char(' '), false)));
+        assert_eq!(parse_key("PageDown"), Some((KeyCode::PageDown, false)));
+        assert_eq!(parse_key("f5"), Some((KeyCode::F(5), false)));
+        assert_eq!(parse_key("hyper-x"), None);
+        assert_eq!(parse_key(""), None);
+    }
+
+    #[test]
+    fn overrides_replace_the_default_keys_of_an_action() {
+        let config = HashMap::from([
+            ("move_up".to_string(), vec!["w".to_string(), "ctrl-p".to_string()]),
+            ("seek_tenths".to_string(), vec!["x".to_string()]),
+            ("quit".to_string(), vec!["hyper-q".to_string()]),
+        ]);
+        let (overrides, errors) = parse_overrides(&config);
+
+        assert_eq!(errors.len(), 2, "{:?}", errors);
+        let keys = apply_overrides(UP, &overrides);
+        let codes: Vec<_> = keys.iter().map(|key| (key.code, key.ctrl)).collect();
+        assert_eq!(codes, [(KeyCode::Char('w'), false), (KeyCode::Char('p'), true)]);
+        assert!(keys.iter().all(|key| key.action == Action::MoveUp));
+        let labels: Vec<_> = keys.iter().map(KeyAction::label).collect();
+        assert_eq!(labels, ["w", "Ctrl-P"]);
+        // 割り当て直していない操作はそのまま
+        let volume = &NOW_PLAYING[3];
+        assert_eq!(apply_overrides(volume.actions, &overrides).len(), volume.actions.len());
+    }
+
+    #[test]
+    fn unlisted_keys_do_nothing() {
+        let press = |page, code| Action::from_key(page, KeyEvent::from(code));
+        assert_eq!(press(Page::NowPlaying, KeyCode::Char('j')), None);
+        assert_eq!(press(Page::Search, KeyCode::Char('e')), None);
+        assert_eq!(press(Page::Devices, KeyCode::Char('x')), None);
+        assert_eq!(
+            Action::from_key(Page::Search, KeyEvent::new(KeyCode::Char('e'), KeyModifiers::CONTROL)),
+            Some(Action::Enqueue)
+        );
+    }
+}
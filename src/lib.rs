@@ -0,0 +1,61 @@
+//! Spotify の再生状態を扱うクライアントと、他の TUI に埋め込めるミニプレイヤー
+//!
+//! - [`SpotifyClient`] と API のモデル（[`api::spotify`]）
+//! - [`SpotifyOAuth`] / [`ProvidedToken`]: アクセストークンの取得
+//! - [`render_mini`]: 再生中の曲を 1〜2 行で描画するウィジェット（`tui` feature）
+//! - [`app::App`]: rs-pod 本体の TUI（`tui` feature）
+//!
+//! `default-features = false` にすると ratatui / crossterm に依存せずに使える。
+
+#[cfg(feature = "tui")]
+pub mod action;
+#[cfg(feature = "tui")]
+mod album_art;
+#[cfg(feature = "tui")]
+mod album_view;
+pub mod api;
+#[cfg(feature = "tui")]
+pub mod app;
+pub mod config;
+#[cfg(feature = "tui")]
+mod continue_queue;
+#[cfg(feature = "tui")]
+pub mod keymap;
+#[cfg(feature = "tui")]
+pub mod mini_player;
+#[cfg(feature = "tui")]
+pub mod notices;
+pub mod utils;
+
+mod image_cache;
+#[cfg(all(feature = "mpris", target_os = "linux"))]
+mod mpris;
+#[cfg(feature = "tui")]
+mod nav;
+#[cfg(feature = "tui")]
+mod now_playing_file;
+#[cfg(feature = "tui")]
+mod playlist_picker;
+#[cfg(feature = "tui")]
+mod playlist_view;
+#[cfg(feature = "tui")]
+mod queue_preview;
+#[cfg(feature = "tui")]
+mod resume;
+#[cfg(feature = "tui")]
+mod search;
+#[cfg(feature = "tui")]
+mod session;
+#[cfg(feature = "tui")]
+mod state_socket;
+#[cfg(feature = "tui")]
+mod theme;
+#[cfg(feature = "tui")]
+mod thumbnails;
+
+pub use api::oauth::{ProvidedToken, SpotifyOAuth};
+pub use api::spotify::{
+    Album, Artist, CurrentUser, Device, Followers, PlayOffset, PlaybackContext, PlayingType, Playlist, RepeatState, SkipDirection, SpotifyClient, SpotifyPlayer, Track,
+};
+#[cfg(feature = "tui")]
+pub use mini_player::render_mini;
@@ -0,0 +1,77 @@
+use color_eyre::Result;
+use std::{fmt::Write as _, fs::OpenOptions, path::Path, sync::Mutex};
+use tracing::{field::Field, Event, Level, Subscriber};
+use tracing_subscriber::{
+    field::Visit,
+    filter::filter_fn,
+    layer::{Context, Layer, SubscriberExt},
+    util::SubscriberInitExt,
+};
+
+/// ログの出力先を決める
+///
+/// `--log-file` があればそのファイルにも追記する。TUI では画面を開く前（認可の案内など）だけ
+/// stderr に出し、代替画面を表示している間は端末に書かず警告以上をステータス行に回す。
+pub fn init(log_file: Option<&Path>, tui: bool) -> Result<()> {
+    let file_layer = match log_file {
+        Some(path) => {
+            let file = OpenOptions::new().create(true).append(true).open(path)?;
+            Some(
+                tracing_subscriber::fmt::layer()
+                    .with_ansi(false)
+                    .with_writer(Mutex::new(file))
+                    .with_filter(tracing_subscriber::filter::LevelFilter::DEBUG),
+            )
+        }
+        None => None,
+    };
+
+    if tui {
+        let stderr_layer = tracing_subscriber::fmt::layer()
+            .with_writer(std::io::stderr)
+            .with_filter(filter_fn(|metadata| {
+                *metadata.level() <= Level::INFO && !rs_pod::notices::tui_active()
+            }));
+        tracing_subscriber::registry()
+            .with(file_layer)
+            .with(stderr_layer)
+            .with(StatusLineLayer)
+            .init();
+    } else if file_layer.is_some() {
+        tracing_subscriber::registry().with(file_layer).init();
+    } else {
+        tracing_subscriber::fmt()
+            .with_max_level(Level::INFO)
+            .with_writer(std::io::stderr)
+            .init();
+    }
+    Ok(())
+}
+
+/// 代替画面の表示中に出た警告とエラーを TUI のステータス行に渡す
+struct StatusLineLayer;
+
+impl<S: Subscriber> Layer<S> for StatusLineLayer {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        if *event.metadata().level() > Level::WARN || !rs_pod::notices::tui_active() {
+            return;
+        }
+        let mut visitor = MessageVisitor::default();
+        event.record(&mut visitor);
+        rs_pod::notices::push(visitor.message);
+    }
+}
+
+/// message フィールドだけを文字列にする
+#[derive(Default)]
+struct MessageVisitor {
+    message: String,
+}
+
+impl Visit for MessageVisitor {
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        if field.name() == "message" {
+            let _ = write!(self.message, "{:?}", value);
+        }
+    }
+}
@@ -1,16 +1,45 @@
-mod api;
-mod app;
-mod utils;
+mod cli;
+mod logging;
 
-use app::App;
+use clap::Parser;
+use cli::Cli;
 use color_eyre::Result;
+use crossterm::{
+    event::{DisableMouseCapture, EnableMouseCapture},
+    execute,
+};
+use rs_pod::{api::oauth::ProvidedToken, app::App};
+use std::io;
 
 #[tokio::main]
 async fn main() -> Result<()> {
     color_eyre::install()?;
+
+    let cli = Cli::parse();
+    logging::init(cli.log_file.as_deref(), cli.command.is_none())?;
+    let provided_token = ProvidedToken::from_arg_or_env(cli.token);
+
+    // サブコマンドは TUI を起動せずに一度だけ実行する
+    if let Some(command) = cli.command {
+        return cli::run(command, provided_token).await;
+    }
+
+    // 認可はブラウザ待ちになることがあるので、Ctrl-C で抜けられるよう raw モードの前に済ませる
+    let mut app = App::new(provided_token).await?;
+
     let mut terminal = ratatui::init();
+    rs_pod::notices::set_tui_active(true);
+    // ratatui::restore はマウスキャプチャを戻さないので、パニック時も含めて自分で解除する
+    let restore_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        let _ = execute!(io::stdout(), DisableMouseCapture);
+        restore_hook(info);
+    }));
+    execute!(io::stdout(), EnableMouseCapture)?;
     terminal.clear()?; // 初回だけクリア
-    let app_result = App::new().await?.run(&mut terminal).await;
+    let app_result = app.run(&mut terminal).await;
+    let _ = execute!(io::stdout(), DisableMouseCapture);
     ratatui::restore();
+    rs_pod::notices::set_tui_active(false);
     app_result
 }
@@ -0,0 +1,83 @@
+use crate::{
+    api::spotify::{SpotifyPlayer, Track},
+    theme,
+    utils::{format_time, join_with_overflow, truncate_with_ellipsis},
+};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Style},
+    text::{Line, Span},
+    widgets::{Gauge, Paragraph, Widget},
+};
+use unicode_width::UnicodeWidthStr;
+
+/// 再生中の曲を 1〜2 行で描画する（他の TUI のステータスバーなどに埋め込む用）
+///
+/// 高さが 2 行以上なら 1 行目に曲名、2 行目にプログレスバーを描く。
+/// 1 行しかなければ曲名の後ろに再生位置を並べる。
+pub fn render_mini(player: &SpotifyPlayer, area: Rect, buf: &mut Buffer) {
+    if area.width == 0 || area.height == 0 {
+        return;
+    }
+    let custom_green = theme::accent();
+
+    let Some(track) = &player.item else {
+        Paragraph::new("No track playing")
+            .style(Style::default().fg(Color::DarkGray))
+            .render(area, buf);
+        return;
+    };
+
+    let progress_ms = player.progress_ms.unwrap_or(0);
+    let icon = if player.is_playing { "▶ " } else { "⏸ " };
+    let times = format!(
+        "{} / {}",
+        format_time(progress_ms),
+        format_time(track.duration_ms)
+    );
+
+    if area.height == 1 {
+        // 時間表示の分を残して曲名を省略する
+        let suffix = format!(" {}", times);
+        let max_width = (area.width as usize).saturating_sub(icon.width() + suffix.width());
+        let line = Line::from(vec![
+            Span::styled(icon, Style::default().fg(custom_green)),
+            Span::raw(title(track, max_width)),
+            Span::styled(suffix, Style::default().fg(Color::DarkGray)),
+        ]);
+        Paragraph::new(line).render(area, buf);
+        return;
+    }
+
+    let layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(1), Constraint::Length(1)])
+        .split(area);
+
+    let max_width = (area.width as usize).saturating_sub(icon.width());
+    let line = Line::from(vec![
+        Span::styled(icon, Style::default().fg(custom_green)),
+        Span::raw(title(track, max_width)),
+    ]);
+    Paragraph::new(line).render(layout[0], buf);
+
+    let ratio = if track.duration_ms > 0 {
+        (progress_ms as f64 / track.duration_ms as f64).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    Gauge::default()
+        .gauge_style(Style::default().fg(custom_green))
+        .ratio(ratio)
+        .label(times)
+        .render(layout[1], buf);
+}
+
+/// 「曲名 — アーティスト」を幅に収める（アーティストが入りきらなければ「+N more」）
+fn title(track: &Track, max_width: usize) -> String {
+    let prefix = format!("{} — ", track.name);
+    let artists_width = max_width.saturating_sub(prefix.width());
+    let artists = join_with_overflow(&track.artist_list(), artists_width);
+    truncate_with_ellipsis(&format!("{}{}", prefix, artists), max_width)
+}
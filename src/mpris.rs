@@ -0,0 +1,131 @@
+use crate::api::spotify::{PlayingType, SpotifyPlayer};
+use mpris_server::{Metadata, PlaybackStatus, Player, Time, TrackId};
+use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
+
+/// メディアキーやデスクトップから届いた操作（App::run のループで実行する）
+#[derive(Debug, Clone, Copy)]
+pub enum MprisCommand {
+    PlayPause,
+    Play,
+    Pause,
+    Next,
+    Previous,
+    /// 現在位置からの相対シーク
+    Seek { offset_ms: i64 },
+    SetPosition { position_ms: i64 },
+}
+
+/// MPRIS2 で rs-pod を公開する（org.mpris.MediaPlayer2.rs_pod）
+///
+/// Player は Send ではないので tokio の LocalSet の中で作る。
+pub struct Mpris {
+    player: Player,
+    commands: UnboundedReceiver<MprisCommand>,
+    /// 前回公開したトラック ID と再生状態（変化したときだけ通知する）
+    published: Option<(Option<String>, bool)>,
+}
+
+impl Mpris {
+    pub async fn new() -> mpris_server::zbus::Result<Self> {
+        let player = Player::builder("rs_pod")
+            .identity("rs-pod")
+            .can_play(true)
+            .can_pause(true)
+            .can_go_next(true)
+            .can_go_previous(true)
+            .can_seek(true)
+            .can_control(true)
+            .build()
+            .await?;
+
+        let (sender, commands) = mpsc::unbounded_channel();
+        connect(&player, &sender);
+        tokio::task::spawn_local(player.run());
+
+        Ok(Self {
+            player,
+            commands,
+            published: None,
+        })
+    }
+
+    pub fn try_recv(&mut self) -> Option<MprisCommand> {
+        self.commands.try_recv().ok()
+    }
+
+    /// ポーリングで取得した状態を反映する
+    pub async fn update(&mut self, player: &SpotifyPlayer) {
+        let progress = Time::from_millis(player.progress_ms.unwrap_or(0));
+        // 位置は毎回更新しても通知は飛ばない
+        self.player.set_position(progress);
+
+        let track_id = player.item.as_ref().and_then(|track| track.id.clone());
+        let state = (track_id, player.is_playing);
+        if self.published.as_ref() == Some(&state) {
+            return;
+        }
+
+        let status = match (&player.item, player.is_playing) {
+            (None, _) => PlaybackStatus::Stopped,
+            (Some(_), true) => PlaybackStatus::Playing,
+            (Some(_), false) => PlaybackStatus::Paused,
+        };
+        let _ = self.player.set_playback_status(status).await;
+        let _ = self.player.set_metadata(metadata(player)).await;
+        self.published = Some(state);
+    }
+}
+
+fn connect(player: &Player, sender: &UnboundedSender<MprisCommand>) {
+    let send = |command: MprisCommand| {
+        let sender = sender.clone();
+        move |_: &Player| {
+            let _ = sender.send(command);
+        }
+    };
+    player.connect_play_pause(send(MprisCommand::PlayPause));
+    player.connect_play(send(MprisCommand::Play));
+    player.connect_pause(send(MprisCommand::Pause));
+    player.connect_stop(send(MprisCommand::Pause));
+    player.connect_next(send(MprisCommand::Next));
+    player.connect_previous(send(MprisCommand::Previous));
+
+    let sender_for_seek = sender.clone();
+    player.connect_seek(move |_, offset| {
+        let _ = sender_for_seek.send(MprisCommand::Seek {
+            offset_ms: offset.as_millis(),
+        });
+    });
+    let sender_for_position = sender.clone();
+    player.connect_set_position(move |_, _, position| {
+        let _ = sender_for_position.send(MprisCommand::SetPosition {
+            position_ms: position.as_millis(),
+        });
+    });
+}
+
+fn metadata(player: &SpotifyPlayer) -> Metadata {
+    let Some(track) = &player.item else {
+        return Metadata::new();
+    };
+
+    let mut builder = Metadata::builder()
+        .title(track.name.clone())
+        .artist(track.artist_list())
+        .length(Time::from_millis(track.duration_ms));
+    // ID はオブジェクトパスに使える英数字だけ（ローカルファイルは ID 無し）
+    if let Some(id) = &track.id
+        && let Ok(track_id) = TrackId::try_from(format!("/org/rs_pod/track/{}", id))
+    {
+        builder = builder.trackid(track_id);
+    }
+    if let Some(image) = track.album.images.first() {
+        builder = builder.art_url(image.url.clone());
+    }
+    if player.currently_playing_type == PlayingType::Episode
+        && let Some(show) = &track.show
+    {
+        builder = builder.album(show.name.clone());
+    }
+    builder.build()
+}
@@ -0,0 +1,74 @@
+/// ブラウザのような「戻る」「進む」の履歴
+#[derive(Debug)]
+pub struct NavHistory<P> {
+    back: Vec<P>,
+    forward: Vec<P>,
+}
+
+impl<P> Default for NavHistory<P> {
+    fn default() -> Self {
+        Self {
+            back: Vec::new(),
+            forward: Vec::new(),
+        }
+    }
+}
+
+impl<P> NavHistory<P> {
+    /// 新しいページへ移動したときに元のページを積む（進む履歴は破棄）
+    pub fn push(&mut self, from: P) {
+        self.back.push(from);
+        self.forward.clear();
+    }
+
+    /// 一つ前のページを返し、現在のページを進む履歴に積む
+    pub fn back(&mut self, current: P) -> Option<P> {
+        let page = self.back.pop()?;
+        self.forward.push(current);
+        Some(page)
+    }
+
+    /// 戻る前のページを返し、現在のページを戻る履歴に積む
+    pub fn forward(&mut self, current: P) -> Option<P> {
+        let page = self.forward.pop()?;
+        self.back.push(current);
+        Some(page)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::NavHistory;
+
+    #[test]
+    fn push_clears_forward_stack() {
+        let mut history = NavHistory::default();
+        history.push(1);
+        assert_eq!(history.back(2), Some(1));
+        // 戻った後に別のページへ移動したら、進む先は無くなる
+        history.push(1);
+        assert_eq!(history.forward(3), None);
+    }
+
+    #[test]
+    fn back_and_forward_round_trip() {
+        let mut history = NavHistory::default();
+        history.push(1);
+        history.push(2);
+        assert_eq!(history.back(3), Some(2));
+        assert_eq!(history.back(2), Some(1));
+        assert_eq!(history.forward(1), Some(2));
+        assert_eq!(history.forward(2), Some(3));
+        assert_eq!(history.forward(3), None);
+        assert_eq!(history.back(3), Some(2));
+    }
+
+    #[test]
+    fn empty_history_has_nowhere_to_go() {
+        let mut history = NavHistory::<u8>::default();
+        assert_eq!(history.back(1), None);
+        assert_eq!(history.forward(1), None);
+        // 失敗した操作で履歴が積まれない
+        assert_eq!(history.back(1), None);
+    }
+}
@@ -0,0 +1,30 @@
+//! TUI の表示中に出た警告をステータス行へ渡すための置き場
+//!
+//! 画面に直接書くと代替画面が崩れるので、ログの購読側がここに積み、App が描画ループで取り出す。
+
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Mutex,
+};
+
+static NOTICES: Mutex<Vec<String>> = Mutex::new(Vec::new());
+/// 代替画面を表示中か（この間は端末に直接書かない）
+static TUI_ACTIVE: AtomicBool = AtomicBool::new(false);
+
+/// ステータス行に出すメッセージを積む
+pub fn push(message: String) {
+    NOTICES.lock().unwrap().push(message);
+}
+
+/// 積まれたメッセージを古い順にすべて取り出す
+pub fn take() -> Vec<String> {
+    std::mem::take(&mut *NOTICES.lock().unwrap())
+}
+
+pub fn set_tui_active(active: bool) {
+    TUI_ACTIVE.store(active, Ordering::Relaxed);
+}
+
+pub fn tui_active() -> bool {
+    TUI_ACTIVE.load(Ordering::Relaxed)
+}
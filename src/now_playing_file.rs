@@ -0,0 +1,47 @@
+use crate::api::spotify::SpotifyPlayer;
+use crate::config::expand_home;
+use std::{fs, io, path::PathBuf};
+
+/// ステータスバー向けに再生中の曲をファイルへ書き出す（内容が変わったときだけ）
+#[derive(Debug)]
+pub struct NowPlayingFile {
+    path: PathBuf,
+    format: String,
+    last_written: Option<String>,
+}
+
+impl NowPlayingFile {
+    pub fn new(path: PathBuf, format: String) -> Self {
+        Self {
+            path: expand_home(path),
+            format,
+            last_written: None,
+        }
+    }
+
+    pub fn update(&mut self, player: &SpotifyPlayer) -> io::Result<()> {
+        let text = self.render(player);
+        if self.last_written.as_ref() == Some(&text) {
+            return Ok(());
+        }
+
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&self.path, format!("{}\n", text))?;
+        self.last_written = Some(text);
+        Ok(())
+    }
+
+    /// 何も再生していなければ空行
+    fn render(&self, player: &SpotifyPlayer) -> String {
+        let Some(track) = &player.item else {
+            return String::new();
+        };
+        let state = if player.is_playing { "▶" } else { "⏸" };
+        self.format
+            .replace("{artist}", &track.artist_names())
+            .replace("{title}", &track.name)
+            .replace("{state}", state)
+    }
+}
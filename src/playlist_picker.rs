@@ -0,0 +1,41 @@
+use crate::utils::{move_selection, Movement};
+use ratatui::widgets::ListState;
+
+/// 再生中の曲を追加するプレイリストを選ぶモーダル
+#[derive(Debug)]
+pub struct PlaylistPicker {
+    /// 追加する曲
+    pub uri: String,
+    pub track_name: String,
+    /// 編集できるプレイリストの App::playlists 内の位置
+    pub playlists: Vec<usize>,
+    pub state: ListState,
+    /// すでに入っていたので追加してよいか確認中のプレイリスト（App::playlists 内の位置）
+    pub confirm_duplicate: Option<usize>,
+}
+
+impl PlaylistPicker {
+    pub fn new(uri: String, track_name: String, playlists: Vec<usize>) -> Self {
+        let mut state = ListState::default();
+        if !playlists.is_empty() {
+            state.select(Some(0));
+        }
+        Self {
+            uri,
+            track_name,
+            playlists,
+            state,
+            confirm_duplicate: None,
+        }
+    }
+
+    pub fn move_selection(&mut self, movement: Movement) {
+        let selected = move_selection(self.state.selected(), self.playlists.len(), movement);
+        self.state.select(selected);
+    }
+
+    /// 選択中のプレイリストの App::playlists 内の位置
+    pub fn selected(&self) -> Option<usize> {
+        self.playlists.get(self.state.selected()?).copied()
+    }
+}
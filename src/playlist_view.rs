@@ -0,0 +1,75 @@
+use crate::api::spotify::{Playlist, Track};
+use crate::utils::{html_to_text, move_selection, Movement};
+use ratatui::widgets::ListState;
+
+/// プレイリストを開いたときの曲一覧
+#[derive(Debug)]
+pub struct PlaylistView {
+    pub playlist: Playlist,
+    pub tracks: Vec<Track>,
+    pub state: ListState,
+    /// 一覧の件数のうち取得できなかった曲
+    pub unavailable: usize,
+    /// フォローしているか（確認できなければ None）
+    pub following: Option<bool>,
+    /// 選択中の曲を削除してよいか確認中
+    pub confirm_remove: bool,
+}
+
+impl PlaylistView {
+    pub fn new(playlist: Playlist, tracks: Vec<Track>, reported: usize) -> Self {
+        let mut state = ListState::default();
+        if !tracks.is_empty() {
+            state.select(Some(0));
+        }
+        Self {
+            playlist,
+            unavailable: reported.saturating_sub(tracks.len()),
+            tracks,
+            state,
+            following: None,
+            confirm_remove: false,
+        }
+    }
+
+    pub fn move_selection(&mut self, movement: Movement) {
+        let selected = move_selection(self.state.selected(), self.tracks.len(), movement);
+        self.state.select(selected);
+    }
+
+    pub fn selected_track(&self) -> Option<&Track> {
+        self.tracks.get(self.state.selected()?)
+    }
+
+    /// 削除した曲を一覧から除く（選択は同じ位置のまま範囲内に収める）
+    pub fn remove_uri(&mut self, uri: &str) {
+        self.tracks.retain(|track| track.uri != uri);
+        let selected = match self.tracks.len() {
+            0 => None,
+            len => Some(self.state.selected().unwrap_or(0).min(len - 1)),
+        };
+        self.state.select(selected);
+    }
+
+    /// 2 つの曲を入れ替えて、移した曲を選んだままにする
+    pub fn swap(&mut self, from: usize, to: usize) {
+        self.tracks.swap(from, to);
+        self.state.select(Some(to));
+    }
+
+    /// 取得できた曲の合計時間（ミリ秒）
+    pub fn total_duration_ms(&self) -> i64 {
+        self.tracks.iter().map(|track| track.duration_ms).sum()
+    }
+
+    pub fn owner_name(&self) -> Option<&str> {
+        self.playlist.owner.as_ref()?.display_name.as_deref()
+    }
+
+    /// HTML を取り除いた説明文（空なら None）
+    pub fn description(&self) -> Option<String> {
+        let text = html_to_text(self.playlist.description.as_deref()?);
+        let text = text.trim();
+        (!text.is_empty()).then(|| text.to_string())
+    }
+}
@@ -0,0 +1,109 @@
+use crate::api::spotify::Track;
+use crate::utils::{move_selection, Movement};
+use ratatui::widgets::ListState;
+use std::collections::VecDeque;
+
+/// まとめてキューに追加する前に順番と対象を確認するリスト
+#[derive(Debug)]
+pub struct QueuePreview {
+    pub playlist_name: String,
+    /// (曲, 追加するか)
+    pub entries: Vec<(Track, bool)>,
+    pub state: ListState,
+    /// 確定後、まだ追加していない URI
+    pending: VecDeque<String>,
+    queued: usize,
+    total: usize,
+    /// 追加に失敗したときのエラー
+    pub error: Option<String>,
+    /// 一覧の件数のうち取得できなかった曲（削除済み・地域制限など）
+    pub unavailable: usize,
+}
+
+impl QueuePreview {
+    /// reported はプレイリスト一覧に出ていた曲数
+    pub fn new(playlist_name: String, tracks: Vec<Track>, reported: usize) -> Self {
+        let mut state = ListState::default();
+        if !tracks.is_empty() {
+            state.select(Some(0));
+        }
+        Self {
+            playlist_name,
+            unavailable: reported.saturating_sub(tracks.len()),
+            entries: tracks.into_iter().map(|track| (track, true)).collect(),
+            state,
+            pending: VecDeque::new(),
+            queued: 0,
+            total: 0,
+            error: None,
+        }
+    }
+
+    pub fn move_selection(&mut self, movement: Movement) {
+        let selected = move_selection(self.state.selected(), self.entries.len(), movement);
+        self.state.select(selected);
+    }
+
+    /// 選択中の曲を上下に入れ替える（カーソルも一緒に動かす）
+    pub fn move_selected(&mut self, up: bool) {
+        let Some(selected) = self.state.selected() else {
+            return;
+        };
+        let target = if up {
+            selected.checked_sub(1)
+        } else {
+            Some(selected + 1).filter(|&i| i < self.entries.len())
+        };
+        if let Some(target) = target {
+            self.entries.swap(selected, target);
+            self.state.select(Some(target));
+        }
+    }
+
+    pub fn toggle_selected(&mut self) {
+        if let Some(selected) = self.state.selected()
+            && let Some((_, included)) = self.entries.get_mut(selected)
+        {
+            *included = !*included;
+        }
+    }
+
+    /// 表示順で追加を開始する
+    pub fn confirm(&mut self) {
+        self.pending = self
+            .entries
+            .iter()
+            .filter(|(track, included)| *included && !track.uri.is_empty())
+            .map(|(track, _)| track.uri.clone())
+            .collect();
+        self.queued = 0;
+        self.total = self.pending.len();
+        self.error = None;
+    }
+
+    /// 次に追加する URI（追加が終わったら mark_queued を呼ぶ）
+    pub fn next_pending(&self) -> Option<&str> {
+        self.pending.front().map(String::as_str)
+    }
+
+    pub fn mark_queued(&mut self) {
+        if self.pending.pop_front().is_some() {
+            self.queued += 1;
+        }
+    }
+
+    /// エラーで追加を中断する
+    pub fn abort(&mut self, error: String) {
+        self.pending.clear();
+        self.error = Some(error);
+    }
+
+    pub fn is_enqueuing(&self) -> bool {
+        !self.pending.is_empty()
+    }
+
+    /// (追加済み, 全体) 。まだ確定していなければ None
+    pub fn progress(&self) -> Option<(usize, usize)> {
+        (self.total > 0).then_some((self.queued, self.total))
+    }
+}
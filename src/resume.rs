@@ -0,0 +1,43 @@
+use crate::config::config_dir;
+use std::{collections::HashMap, fs, io};
+
+const RESUME_FILE: &str = "resume_points.json";
+
+/// 最後まで聴き終えたとみなす残り時間
+const FINISHED_MARGIN_MS: i64 = 30_000;
+
+/// エピソードごとの再生位置 `{episode_id: position_ms}`
+#[derive(Debug, Default)]
+pub struct ResumePoints {
+    positions: HashMap<String, i64>,
+}
+
+impl ResumePoints {
+    pub fn load() -> Self {
+        let positions = fs::read_to_string(config_dir().join(RESUME_FILE))
+            .ok()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default();
+        Self { positions }
+    }
+
+    pub fn get(&self, episode_id: &str) -> Option<i64> {
+        self.positions.get(episode_id).copied()
+    }
+
+    /// 再生位置を更新（終盤まで聴いたものは削除）
+    pub fn update(&mut self, episode_id: &str, position_ms: i64, duration_ms: i64) {
+        if duration_ms > 0 && position_ms >= duration_ms - FINISHED_MARGIN_MS {
+            self.positions.remove(episode_id);
+        } else {
+            self.positions.insert(episode_id.to_string(), position_ms);
+        }
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        let dir = config_dir();
+        fs::create_dir_all(&dir)?;
+        let json = serde_json::to_string_pretty(&self.positions)?;
+        fs::write(dir.join(RESUME_FILE), json)
+    }
+}
@@ -0,0 +1,116 @@
+use crate::api::spotify::{SpotifyClient, Track};
+use crate::utils::{move_selection, Movement};
+use ratatui::widgets::ListState;
+use std::time::{Duration, Instant};
+use tokio::task::JoinHandle;
+
+/// 最後のキー入力からこれだけ待ってから検索する（1 文字ごとに API を叩かない）
+const DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// 検索を始めたときの generation と結果
+type SearchResult = (u64, Result<Vec<Track>, String>);
+
+/// 入力しながら曲を検索するページの状態
+#[derive(Debug, Default)]
+pub struct Search {
+    pub query: String,
+    pub results: Vec<Track>,
+    pub state: ListState,
+    pub error: Option<String>,
+    /// この時刻を過ぎたら検索する
+    due: Option<Instant>,
+    /// クエリを変えるたびに増やし、古い検索の結果を捨てる
+    generation: u64,
+    task: Option<JoinHandle<SearchResult>>,
+}
+
+impl Search {
+    pub fn push(&mut self, c: char) {
+        self.query.push(c);
+        self.schedule();
+    }
+
+    pub fn pop(&mut self) {
+        if self.query.pop().is_some() {
+            self.schedule();
+        }
+    }
+
+    /// 入力が止まるのを待って検索し直す（実行中の検索は結果を待たずに中断）
+    fn schedule(&mut self) {
+        self.generation += 1;
+        if let Some(task) = self.task.take() {
+            task.abort();
+        }
+        if self.query.trim().is_empty() {
+            self.due = None;
+            self.results.clear();
+            self.state.select(None);
+            self.error = None;
+        } else {
+            self.due = Some(Instant::now() + DEBOUNCE);
+        }
+    }
+
+    pub fn is_searching(&self) -> bool {
+        self.due.is_some() || self.task.is_some()
+    }
+
+    /// 次に検索するまでの時間（イベント待ちのタイムアウトを縮めるのに使う）
+    pub fn time_until_due(&self) -> Option<Duration> {
+        Some(self.due?.saturating_duration_since(Instant::now()))
+    }
+
+    /// 待ち時間が過ぎていれば検索を始め、終わった検索があれば結果を反映する
+    pub async fn poll(&mut self, client: &SpotifyClient) {
+        if self.due.is_some_and(|due| Instant::now() >= due) {
+            self.due = None;
+            let client = client.clone();
+            let query = self.query.trim().to_string();
+            let generation = self.generation;
+            self.task = Some(tokio::spawn(async move {
+                let result = client.search_tracks(&query).await.map_err(|e| e.to_string());
+                (generation, result)
+            }));
+        }
+
+        if !self.task.as_ref().is_some_and(JoinHandle::is_finished) {
+            return;
+        }
+        let Some(task) = self.task.take() else {
+            return;
+        };
+        let Ok((generation, result)) = task.await else {
+            return;
+        };
+        // 検索中にクエリが変わっていたら古い結果は使わない
+        if generation != self.generation {
+            return;
+        }
+        match result {
+            Ok(tracks) => {
+                self.state.select((!tracks.is_empty()).then_some(0));
+                self.results = tracks;
+                self.error = None;
+            }
+            Err(e) => self.error = Some(e),
+        }
+    }
+
+    pub fn move_selection(&mut self, movement: Movement) {
+        let selected = move_selection(self.state.selected(), self.results.len(), movement);
+        self.state.select(selected);
+    }
+
+    pub fn selected_track(&self) -> Option<&Track> {
+        self.results.get(self.state.selected()?)
+    }
+}
+
+impl Drop for Search {
+    fn drop(&mut self) {
+        if let Some(task) = self.task.take() {
+            task.abort();
+        }
+    }
+}
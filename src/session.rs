@@ -0,0 +1,68 @@
+use crate::app::Page;
+use crate::config::config_dir;
+use serde::{Deserialize, Serialize};
+use std::{
+    fs, io,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+const SESSION_FILE: &str = "session.json";
+
+/// 前回終了したときのページと選択していたプレイリスト
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct Session {
+    pub page: Option<Page>,
+    pub playlist_id: Option<String>,
+    /// 終了したときに再生していた曲
+    pub last_played: Option<LastPlayed>,
+}
+
+/// 次に起動したときに続きから再生するための情報
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct LastPlayed {
+    pub uri: String,
+    pub name: String,
+    /// プレイリストやアルバムから再生していれば、続きもそこから再生する
+    pub context_uri: Option<String>,
+    pub progress_ms: i64,
+    /// 保存した時刻（UNIX 秒）
+    pub saved_at: u64,
+}
+
+impl LastPlayed {
+    pub fn new(uri: String, name: String, context_uri: Option<String>, progress_ms: i64) -> Self {
+        let saved_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_secs());
+        Self {
+            uri,
+            name,
+            context_uri,
+            progress_ms,
+            saved_at,
+        }
+    }
+
+    /// 保存してから max_age 以内か
+    pub fn is_recent(&self, max_age: Duration) -> bool {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH + Duration::from_secs(self.saved_at))
+            .is_ok_and(|age| age <= max_age)
+    }
+}
+
+impl Session {
+    pub fn load() -> Self {
+        fs::read_to_string(config_dir().join(SESSION_FILE))
+            .ok()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        let dir = config_dir();
+        fs::create_dir_all(&dir)?;
+        let json = serde_json::to_string_pretty(self)?;
+        fs::write(dir.join(SESSION_FILE), json)
+    }
+}
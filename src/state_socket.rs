@@ -0,0 +1,168 @@
+use crate::api::spotify::SpotifyPlayer;
+#[cfg(unix)]
+use crate::config::expand_home;
+#[cfg(unix)]
+use std::path::Path;
+use std::{io, path::PathBuf};
+use tokio::{
+    io::{AsyncWrite, AsyncWriteExt},
+    sync::watch,
+    task::JoinHandle,
+};
+
+/// 外部スクリプト向けに再生状態を JSON 行で配信する
+///
+/// Unix ではドメインソケット、それ以外ではループバックの TCP ポートで待ち受ける。
+/// 接続するとまず現在の状態が 1 行届き、以降は状態が変わるたびに 1 行ずつ届く。
+pub struct StateSocket {
+    state: watch::Sender<String>,
+    accept_task: JoinHandle<()>,
+    #[cfg(unix)]
+    path: PathBuf,
+}
+
+impl StateSocket {
+    #[cfg(unix)]
+    pub fn bind(path: PathBuf, _port: u16) -> io::Result<Self> {
+        let path = expand_home(path);
+        remove_stale_socket(&path)?;
+        let listener = tokio::net::UnixListener::bind(&path)?;
+
+        let (state, receiver) = watch::channel(String::new());
+        let accept_task = tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                tokio::spawn(serve(stream, receiver.clone()));
+            }
+        });
+        Ok(Self {
+            state,
+            accept_task,
+            path,
+        })
+    }
+
+    #[cfg(not(unix))]
+    pub fn bind(_path: PathBuf, port: u16) -> io::Result<Self> {
+        let listener = std::net::TcpListener::bind(("127.0.0.1", port))?;
+        listener.set_nonblocking(true)?;
+        let listener = tokio::net::TcpListener::from_std(listener)?;
+
+        let (state, receiver) = watch::channel(String::new());
+        let accept_task = tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                tokio::spawn(serve(stream, receiver.clone()));
+            }
+        });
+        Ok(Self { state, accept_task })
+    }
+
+    /// 状態が変わっていれば接続中のクライアントに送る
+    pub fn update(&self, player: &SpotifyPlayer) {
+        let line = player.state_json().to_string();
+        self.state.send_if_modified(|current| {
+            if *current == line {
+                return false;
+            }
+            *current = line;
+            true
+        });
+    }
+}
+
+impl Drop for StateSocket {
+    fn drop(&mut self) {
+        self.accept_task.abort();
+        #[cfg(unix)]
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+/// 前回異常終了したときのソケットが残っていると bind できないので消す
+///
+/// 消すのは誰も待ち受けていないソケットだけで、普通のファイルや使用中のソケットならエラーにする。
+#[cfg(unix)]
+fn remove_stale_socket(path: &Path) -> io::Result<()> {
+    use std::os::unix::fs::FileTypeExt;
+
+    let metadata = match std::fs::symlink_metadata(path) {
+        Ok(metadata) => metadata,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(()),
+        Err(e) => return Err(e),
+    };
+    if !metadata.file_type().is_socket() {
+        return Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            format!("{} exists and is not a socket", path.display()),
+        ));
+    }
+    if std::os::unix::net::UnixStream::connect(path).is_ok() {
+        return Err(io::Error::new(
+            io::ErrorKind::AddrInUse,
+            format!("{} is already in use by another process", path.display()),
+        ));
+    }
+    std::fs::remove_file(path)
+}
+
+/// 1 クライアント分の配信（書き込みに失敗したら切断とみなして終える）
+async fn serve<S: AsyncWrite + Unpin>(mut stream: S, mut state: watch::Receiver<String>) {
+    loop {
+        let line = format!("{}\n", *state.borrow_and_update());
+        // まだ一度も取得していない間は送らない
+        if line.len() > 1 && stream.write_all(line.as_bytes()).await.is_err() {
+            return;
+        }
+        // 送信側（App）が終了したら接続も閉じる
+        if state.changed().await.is_err() {
+            let _ = stream.shutdown().await;
+            return;
+        }
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::remove_stale_socket;
+    use std::{env, fs, io, os::unix::net::UnixListener, path::PathBuf, process};
+
+    fn temp_path(name: &str) -> PathBuf {
+        let path = env::temp_dir().join(format!("rs-pod-state-socket-{}-{}", name, process::id()));
+        let _ = fs::remove_file(&path);
+        path
+    }
+
+    #[test]
+    fn missing_path_is_fine() {
+        assert!(remove_stale_socket(&temp_path("missing")).is_ok());
+    }
+
+    #[test]
+    fn regular_file_is_kept() {
+        let path = temp_path("file");
+        fs::write(&path, "notes").unwrap();
+        let err = remove_stale_socket(&path).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::AlreadyExists);
+        assert_eq!(fs::read_to_string(&path).unwrap(), "notes");
+        let _ = fs::remove_file(path);
+    }
+
+    #[test]
+    fn live_socket_is_kept() {
+        let path = temp_path("live");
+        let _listener = UnixListener::bind(&path).unwrap();
+        let err = remove_stale_socket(&path).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::AddrInUse);
+        assert!(path.exists());
+        let _ = fs::remove_file(path);
+    }
+
+    #[test]
+    fn stale_socket_is_removed() {
+        let path = temp_path("stale");
+        // 閉じてもソケットファイルは残る
+        drop(UnixListener::bind(&path).unwrap());
+        assert!(path.exists());
+        remove_stale_socket(&path).unwrap();
+        assert!(!path.exists());
+    }
+}
@@ -0,0 +1,133 @@
+//! 端末の色数に合わせた表示の色
+//!
+//! 24bit 色に対応していない端末では、アクセントの緑などを 256 色や 16 色の近い色に置き換える。
+
+use crate::config::Palette;
+use ratatui::style::Color;
+use std::{env, sync::OnceLock};
+
+/// アクセントの緑（24bit 色のとき）
+const ACCENT: Color = Color::Rgb(0x0A, 0xE1, 0x64);
+
+/// 16 色それぞれの一般的な RGB（端末ごとに多少違う）
+const ANSI16: [(Color, (u8, u8, u8)); 16] = [
+    (Color::Black, (0, 0, 0)),
+    (Color::Red, (128, 0, 0)),
+    (Color::Green, (0, 128, 0)),
+    (Color::Yellow, (128, 128, 0)),
+    (Color::Blue, (0, 0, 128)),
+    (Color::Magenta, (128, 0, 128)),
+    (Color::Cyan, (0, 128, 128)),
+    (Color::Gray, (192, 192, 192)),
+    (Color::DarkGray, (128, 128, 128)),
+    (Color::LightRed, (255, 0, 0)),
+    (Color::LightGreen, (0, 255, 0)),
+    (Color::LightYellow, (255, 255, 0)),
+    (Color::LightBlue, (0, 0, 255)),
+    (Color::LightMagenta, (255, 0, 255)),
+    (Color::LightCyan, (0, 255, 255)),
+    (Color::White, (255, 255, 255)),
+];
+
+/// 256 色の 6x6x6 の色立方体の各段階の値
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+static COLOR_DEPTH: OnceLock<ColorDepth> = OnceLock::new();
+
+/// 端末で使える色数
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorDepth {
+    Truecolor,
+    Ansi256,
+    Ansi16,
+}
+
+impl ColorDepth {
+    /// 設定で決め打ちされていなければ COLORTERM / TERM から判断する
+    fn detect(palette: Palette) -> Self {
+        match palette {
+            Palette::Truecolor => Self::Truecolor,
+            Palette::Ansi256 => Self::Ansi256,
+            Palette::Ansi16 => Self::Ansi16,
+            Palette::Auto => Self::from_env(),
+        }
+    }
+
+    fn from_env() -> Self {
+        let colorterm = env::var("COLORTERM").unwrap_or_default();
+        // Windows Terminal は COLORTERM を設定しないが 24bit 色に対応している
+        if matches!(colorterm.as_str(), "truecolor" | "24bit") || env::var_os("WT_SESSION").is_some() {
+            return Self::Truecolor;
+        }
+        if env::var("TERM").is_ok_and(|term| term.contains("256color")) {
+            Self::Ansi256
+        } else {
+            Self::Ansi16
+        }
+    }
+}
+
+/// 起動時に設定の palette で色数を決める（2 回目以降は無視）
+pub fn init(palette: Palette) {
+    let depth = *COLOR_DEPTH.get_or_init(|| ColorDepth::detect(palette));
+    tracing::debug!(?depth, "Detected color depth");
+}
+
+/// 色数（init が呼ばれていなければ環境変数から判断する）
+pub fn color_depth() -> ColorDepth {
+    *COLOR_DEPTH.get_or_init(|| ColorDepth::detect(Palette::Auto))
+}
+
+/// アクセントの緑
+pub fn accent() -> Color {
+    adapt(ACCENT)
+}
+
+/// RGB の色を端末の色数で出せる近い色にする（色名やインデックスの色はそのまま）
+pub fn adapt(color: Color) -> Color {
+    let Color::Rgb(r, g, b) = color else {
+        return color;
+    };
+    match color_depth() {
+        ColorDepth::Truecolor => color,
+        ColorDepth::Ansi256 => Color::Indexed(nearest_256(r, g, b)),
+        ColorDepth::Ansi16 => nearest_16(r, g, b),
+    }
+}
+
+fn distance((r1, g1, b1): (u8, u8, u8), (r2, g2, b2): (u8, u8, u8)) -> u32 {
+    let d = |a: u8, b: u8| (a as i32 - b as i32).pow(2) as u32;
+    d(r1, r2) + d(g1, g2) + d(b1, b2)
+}
+
+/// 色立方体（16〜231）とグレースケール（232〜255）のうち一番近い色
+fn nearest_256(r: u8, g: u8, b: u8) -> u8 {
+    let level = |v: u8| {
+        (0..CUBE_LEVELS.len())
+            .min_by_key(|&i| (CUBE_LEVELS[i] as i32 - v as i32).abs())
+            .unwrap_or(0)
+    };
+    let (ri, gi, bi) = (level(r), level(g), level(b));
+    let cube = (CUBE_LEVELS[ri], CUBE_LEVELS[gi], CUBE_LEVELS[bi]);
+    let cube_index = (16 + 36 * ri + 6 * gi + bi) as u8;
+
+    // グレーは 8, 18, ..., 238 の 24 段階
+    let average = (r as u32 + g as u32 + b as u32) / 3;
+    let gray_step = (average.saturating_sub(3) / 10).min(23) as u8;
+    let gray_value = 8 + gray_step * 10;
+    let gray = (gray_value, gray_value, gray_value);
+
+    if distance((r, g, b), gray) < distance((r, g, b), cube) {
+        232 + gray_step
+    } else {
+        cube_index
+    }
+}
+
+fn nearest_16(r: u8, g: u8, b: u8) -> Color {
+    ANSI16
+        .iter()
+        .min_by_key(|(_, rgb)| distance((r, g, b), *rgb))
+        .map(|(color, _)| *color)
+        .unwrap_or(Color::Green)
+}
@@ -0,0 +1,90 @@
+use crate::api::spotify::SpotifyClient;
+use image::DynamicImage;
+use ratatui::{buffer::Buffer, layout::Rect, widgets::StatefulWidget};
+use ratatui_image::{picker::Picker, protocol::StatefulProtocol, StatefulImage};
+use std::collections::HashMap;
+use tokio::task::JoinHandle;
+
+/// 縮小してから持っておく画像の大きさ（ピクセル、サムネイルには十分）
+const THUMBNAIL_PIXELS: u32 = 96;
+
+enum Thumbnail {
+    Loading(JoinHandle<Result<DynamicImage, String>>),
+    Ready(Box<dyn StatefulProtocol>),
+    Failed,
+}
+
+/// プレイリスト一覧の小さなカバー画像（URL ごとに一度だけ取得する）
+pub struct Thumbnails {
+    /// 画像の形式を調べていなければ（テスト用の App など）常に代わりの四角を出す
+    picker: Option<Picker>,
+    images: HashMap<String, Thumbnail>,
+}
+
+impl Thumbnails {
+    pub fn new(picker: Option<Picker>) -> Self {
+        Self {
+            picker,
+            images: HashMap::new(),
+        }
+    }
+
+    /// まだ取得していなければ取得を始める（先読みでキャッシュ済みならすぐ終わる）
+    pub fn request(&mut self, client: &SpotifyClient, url: &str) {
+        if self.picker.is_none() || self.images.contains_key(url) {
+            return;
+        }
+        let client = client.clone();
+        let task_url = url.to_string();
+        let task = tokio::spawn(async move {
+            client
+                .download_image(&task_url)
+                .await
+                .map(|image| image.thumbnail(THUMBNAIL_PIXELS, THUMBNAIL_PIXELS))
+                .map_err(|e| e.to_string())
+        });
+        self.images.insert(url.to_string(), Thumbnail::Loading(task));
+    }
+
+    /// 取得が終わったものを描画できる形にする
+    pub async fn poll(&mut self) {
+        let Some(picker) = self.picker.as_mut() else {
+            return;
+        };
+        for (url, thumbnail) in self.images.iter_mut() {
+            let Thumbnail::Loading(task) = thumbnail else {
+                continue;
+            };
+            if !task.is_finished() {
+                continue;
+            }
+            *thumbnail = match task.await {
+                Ok(Ok(image)) => Thumbnail::Ready(picker.new_resize_protocol(image)),
+                Ok(Err(e)) => {
+                    tracing::debug!(error = %e, url, "Failed to load playlist thumbnail");
+                    Thumbnail::Failed
+                }
+                Err(_) => Thumbnail::Failed,
+            };
+        }
+    }
+
+    /// 取得済みなら描いて true（取得中・失敗・非対応なら false）
+    pub fn render(&mut self, url: &str, area: Rect, buf: &mut Buffer) -> bool {
+        let Some(Thumbnail::Ready(image)) = self.images.get_mut(url) else {
+            return false;
+        };
+        StatefulImage::new(None).render(area, buf, image);
+        true
+    }
+}
+
+impl Drop for Thumbnails {
+    fn drop(&mut self) {
+        for thumbnail in self.images.values() {
+            if let Thumbnail::Loading(task) = thumbnail {
+                task.abort();
+            }
+        }
+    }
+}
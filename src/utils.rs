@@ -1,3 +1,6 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
 // ミリ秒をmm:ss形式にフォーマット
 pub fn format_time(ms: i64) -> String {
     let total_seconds = ms / 1000;
@@ -5,3 +8,309 @@ pub fn format_time(ms: i64) -> String {
     let seconds = total_seconds % 60;
     format!("{}:{:02}", minutes, seconds)
 }
+
+/// 長い時間を「1h 23m」のように表示する（1 時間未満は「23m」）
+pub fn format_duration_long(ms: i64) -> String {
+    let total_minutes = ms.max(0) / 60_000;
+    let hours = total_minutes / 60;
+    let minutes = total_minutes % 60;
+    if hours > 0 {
+        format!("{}h {}m", hours, minutes)
+    } else {
+        format!("{}m", minutes)
+    }
+}
+
+/// マーキー表示で末尾と先頭の間に挟む余白
+const MARQUEE_GAP: &str = "   ";
+
+/// 表示幅に収まるように末尾を「…」で省略する（全角文字は幅2として数える）
+pub fn truncate_with_ellipsis(s: &str, max_width: usize) -> String {
+    if s.width() <= max_width {
+        return s.to_string();
+    }
+    if max_width == 0 {
+        return String::new();
+    }
+
+    let mut result = String::new();
+    let mut width = 0;
+    for c in s.chars() {
+        let char_width = c.width().unwrap_or(0);
+        // 「…」の1列分を残す
+        if width + char_width > max_width - 1 {
+            break;
+        }
+        result.push(c);
+        width += char_width;
+    }
+    result.push('…');
+    result
+}
+
+/// 表示幅を超える文字列を offset 列ずらして切り出す（末尾まで行くと先頭に戻る）
+pub fn marquee(s: &str, max_width: usize, offset: usize) -> String {
+    if s.width() <= max_width {
+        return s.to_string();
+    }
+
+    let looped: Vec<(char, usize)> = s
+        .chars()
+        .chain(MARQUEE_GAP.chars())
+        .map(|c| (c, c.width().unwrap_or(0)))
+        .collect();
+    let cycle_width: usize = looped.iter().map(|(_, w)| w).sum();
+    let start_column = offset % cycle_width;
+
+    // 開始列以降の最初の文字から始める（全角文字の途中では切らない）
+    let mut column = 0;
+    let start = looped
+        .iter()
+        .position(|(_, w)| {
+            let found = column >= start_column;
+            column += w;
+            found
+        })
+        .unwrap_or(0);
+
+    let mut result = String::new();
+    let mut width = 0;
+    for (c, char_width) in looped.iter().cycle().skip(start) {
+        if width + char_width > max_width {
+            break;
+        }
+        result.push(*c);
+        width += char_width;
+    }
+    result
+}
+
+/// 表示幅がちょうど width になるよう、長ければ省略し短ければ右を空白で埋める
+pub fn pad_to_width(s: &str, width: usize) -> String {
+    let text = truncate_with_ellipsis(s, width);
+    let padding = width.saturating_sub(text.width());
+    format!("{}{}", text, " ".repeat(padding))
+}
+
+/// 曲名・アーティスト・長さを列に揃えた1行（長さは右寄せで常に表示する）
+pub fn track_columns(name: &str, artists: &str, duration_ms: i64, width: usize) -> String {
+    // 長さは "mmm:ss" まで入る幅を確保する
+    const DURATION_WIDTH: usize = 6;
+    const GAP: usize = 2;
+
+    let text_width = width.saturating_sub(DURATION_WIDTH + GAP * 2);
+    let name_width = text_width * 3 / 5;
+    let artist_width = text_width - name_width;
+    format!(
+        "{}{gap}{}{gap}{:>DURATION_WIDTH$}",
+        pad_to_width(name, name_width),
+        pad_to_width(artists, artist_width),
+        format_time(duration_ms),
+        gap = " ".repeat(GAP),
+    )
+}
+
+/// 表示幅に収まるだけ「, 」で連結し、入りきらなかった数を「 +N more」で示す
+pub fn join_with_overflow(items: &[&str], max_width: usize) -> String {
+    for shown in (1..=items.len()).rev() {
+        let mut text = items[..shown].join(", ");
+        if shown < items.len() {
+            text.push_str(&format!(" +{} more", items.len() - shown));
+        }
+        if text.width() <= max_width {
+            return text;
+        }
+    }
+
+    // 1件目すら入らない場合は省略して詰める
+    match items {
+        [] => String::new(),
+        [only] => truncate_with_ellipsis(only, max_width),
+        [first, rest @ ..] => {
+            let suffix = format!(" +{} more", rest.len());
+            let name_width = max_width.saturating_sub(suffix.width());
+            if name_width == 0 {
+                return truncate_with_ellipsis(&suffix, max_width);
+            }
+            format!("{}{}", truncate_with_ellipsis(first, name_width), suffix)
+        }
+    }
+}
+
+/// Spotify の説明文（HTML）からタグを除き、&amp; などの文字参照を戻す
+pub fn html_to_text(html: &str) -> String {
+    let mut text = String::new();
+    let mut rest = html;
+    while let Some(start) = rest.find('<') {
+        text.push_str(&rest[..start]);
+        match rest[start..].find('>') {
+            Some(end) => rest = &rest[start + end + 1..],
+            None => {
+                rest = &rest[start..];
+                break;
+            }
+        }
+    }
+    text.push_str(rest);
+    decode_html_entities(&text)
+}
+
+fn decode_html_entities(s: &str) -> String {
+    let mut result = String::new();
+    let mut rest = s;
+    while let Some(start) = rest.find('&') {
+        result.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let decoded = rest.find(';').filter(|&end| end <= 10).and_then(|end| {
+            let entity = &rest[1..end];
+            let c = match entity {
+                "amp" => Some('&'),
+                "lt" => Some('<'),
+                "gt" => Some('>'),
+                "quot" => Some('"'),
+                "apos" => Some('\''),
+                "nbsp" => Some(' '),
+                _ => entity
+                    .strip_prefix("#x")
+                    .or_else(|| entity.strip_prefix("#X"))
+                    .map(|hex| u32::from_str_radix(hex, 16))
+                    .or_else(|| entity.strip_prefix('#').map(|dec| dec.parse()))
+                    .and_then(Result::ok)
+                    .and_then(char::from_u32),
+            }?;
+            Some((c, end))
+        });
+        match decoded {
+            Some((c, end)) => {
+                result.push(c);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                result.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    result.push_str(rest);
+    result
+}
+
+/// query の文字が順番どおりに text に含まれていればマッチ（大文字小文字は区別しない）
+pub fn fuzzy_match(query: &str, text: &str) -> bool {
+    let mut text_chars = text.chars().flat_map(char::to_lowercase);
+    query
+        .chars()
+        .flat_map(char::to_lowercase)
+        .filter(|c| !c.is_whitespace())
+        .all(|q| text_chars.any(|t| t == q))
+}
+
+/// プログレスバーの割合（0〜100）。次の曲が来る前に duration を超えても 100 で止める
+pub fn progress_percent(progress_ms: i64, duration_ms: i64) -> u16 {
+    if duration_ms <= 0 {
+        return 0;
+    }
+    // 浮動小数点の丸めで終端が 99% にならないよう整数で計算する
+    (progress_ms.clamp(0, duration_ms) * 100 / duration_ms) as u16
+}
+
+/// リストの選択をどちらへ動かすか
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Movement {
+    Previous,
+    Next,
+    /// 指定した行数だけ上へ（先頭で止まる）
+    PageUp(usize),
+    /// 指定した行数だけ下へ（末尾で止まる）
+    PageDown(usize),
+    First,
+    Last,
+}
+
+/// 長さ len のリストで選択を動かした後の位置（1行ずつなら端で反対側へ回り込む、空なら None）
+pub fn move_selection(selected: Option<usize>, len: usize, movement: Movement) -> Option<usize> {
+    if len == 0 {
+        return None;
+    }
+    let last = len.saturating_sub(1);
+    let Some(selected) = selected else {
+        return Some(if movement == Movement::Last { last } else { 0 });
+    };
+    let next = match movement {
+        Movement::Previous if selected == 0 => last,
+        Movement::Previous => selected - 1,
+        Movement::Next if selected >= last => 0,
+        Movement::Next => selected + 1,
+        Movement::PageUp(rows) => selected.saturating_sub(rows.max(1)),
+        Movement::PageDown(rows) => selected.saturating_add(rows.max(1)).min(last),
+        Movement::First => 0,
+        Movement::Last => last,
+    };
+    Some(next)
+}
+
+/// 端末のタイムゾーンでの時刻（時, 分）。タイムゾーンが分からなければ UTC
+pub fn local_time_of_day(time: SystemTime) -> (u32, u32) {
+    let secs = time.duration_since(UNIX_EPOCH).map_or(0, |since| since.as_secs());
+    #[cfg(unix)]
+    {
+        // localtime_r は渡した tm にだけ書き込む（localtime と違ってスレッドをまたいでも安全）
+        let timestamp = secs as libc::time_t;
+        let mut tm: libc::tm = unsafe { std::mem::zeroed() };
+        if !unsafe { libc::localtime_r(&timestamp, &mut tm) }.is_null() {
+            return (tm.tm_hour as u32, tm.tm_min as u32);
+        }
+    }
+    ((secs / 3600 % 24) as u32, (secs / 60 % 60) as u32)
+}
+
+/// ロケールが UTF-8 か（LC_ALL / LC_CTYPE / LANG の順に見る、どれも無ければ UTF-8 とみなす）
+pub fn locale_is_utf8() -> bool {
+    ["LC_ALL", "LC_CTYPE", "LANG"]
+        .iter()
+        .filter_map(|name| std::env::var(name).ok())
+        .find(|value| !value.is_empty())
+        .is_none_or(|value| {
+            let value = value.to_lowercase();
+            value.contains("utf-8") || value.contains("utf8")
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{local_time_of_day, progress_percent};
+    use std::time::{Duration, UNIX_EPOCH};
+
+    #[test]
+    fn progress_percent_reaches_exactly_100_at_the_end() {
+        assert_eq!(progress_percent(0, 215_000), 0);
+        assert_eq!(progress_percent(107_500, 215_000), 50);
+        // 終端はちょうど 100
+        assert_eq!(progress_percent(215_000, 215_000), 100);
+        assert_eq!(progress_percent(1, 3), 33);
+        assert_eq!(progress_percent(3, 3), 100);
+    }
+
+    #[test]
+    fn progress_percent_clamps_overshoot_and_negative_progress() {
+        assert_eq!(progress_percent(230_000, 215_000), 100);
+        assert_eq!(progress_percent(i64::MAX / 100, 215_000), 100);
+        assert_eq!(progress_percent(-500, 215_000), 0);
+    }
+
+    #[test]
+    fn progress_percent_is_zero_without_duration() {
+        assert_eq!(progress_percent(0, 0), 0);
+        assert_eq!(progress_percent(5_000, 0), 0);
+        assert_eq!(progress_percent(5_000, -1), 0);
+    }
+
+    #[test]
+    fn local_time_of_day_is_a_clock_time() {
+        // 2024-05-17 12:34 UTC。時差はどの地域も 15 分単位なので、分の端数は UTC と同じ
+        let time = UNIX_EPOCH + Duration::from_secs(1_715_949_240);
+        let (hour, minute) = local_time_of_day(time);
+        assert!(hour < 24 && minute < 60, "{}:{}", hour, minute);
+        assert_eq!(minute % 15, 34 % 15);
+    }
+}
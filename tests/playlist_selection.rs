@@ -0,0 +1,156 @@
+//! プレイリスト一覧での選択とページ遷移
+//!
+//! Spotify API の代わりに手元で tiny_http のスタブを立て、`App::with_client` で向き先を変えて動かす。
+#![cfg(feature = "tui")]
+
+use crossterm::event::{KeyCode, KeyEvent};
+use rs_pod::action::Action;
+use rs_pod::app::{App, Page};
+use rs_pod::config::Config;
+use rs_pod::{Device, SpotifyClient};
+use std::thread;
+use tiny_http::{Header, Response, Server};
+
+const PLAYLIST_NAMES: [&str; 3] = ["Morning", "Focus", "Night"];
+
+/// /v1/me/playlists に playlists を返し、それ以外（再生など）は 204 を返すスタブ
+fn spawn_stub_api(playlists: String) -> String {
+    let server = Server::http("127.0.0.1:0").expect("failed to start stub server");
+    let base_url = format!("http://{}", server.server_addr());
+    thread::spawn(move || {
+        for request in server.incoming_requests() {
+            let response = if request.url().starts_with("/v1/me/playlists") {
+                let header = Header::from_bytes("Content-Type", "application/json").unwrap();
+                Response::from_string(playlists.clone()).with_header(header)
+            } else {
+                Response::from_string("").with_status_code(204)
+            };
+            let _ = request.respond(response);
+        }
+    });
+    base_url
+}
+
+fn playlists_json() -> String {
+    let items: Vec<String> = PLAYLIST_NAMES
+        .iter()
+        .enumerate()
+        .map(|(i, name)| {
+            format!(
+                r#"{{"id":"playlist{}","name":"{}","tracks":{{"total":10}},"images":[],"owner":null}}"#,
+                i, name
+            )
+        })
+        .collect();
+    format!(r#"{{"items":[{}]}}"#, items.join(","))
+}
+
+async fn app_with_playlists() -> App {
+    app_with_playlists_json(playlists_json()).await
+}
+
+async fn app_with_playlists_json(playlists: String) -> App {
+    let mut client =
+        SpotifyClient::new(reqwest::Client::new(), &"test-token".to_string()).with_base_url(spawn_stub_api(playlists));
+    // デバイスが無いと再生画面の案内から始まるので、再生中のデバイスがある状態にする
+    client.spotify_player.device = Some(Device {
+        id: Some("device0".to_string()),
+        name: "Test device".to_string(),
+        is_active: true,
+        volume_percent: Some(50),
+        supports_volume: true,
+    });
+    let config = Config {
+        resume_episodes: false,
+        ..Config::default()
+    };
+    let mut app = App::with_client(client, config);
+    app.wait_for_playlists().await;
+    app
+}
+
+async fn press(app: &mut App, code: KeyCode) {
+    if let Some(action) = Action::from_key(app.current_page(), KeyEvent::from(code)) {
+        app.apply_action(action).await;
+    }
+}
+
+fn selected_name(app: &App) -> Option<&str> {
+    app.selected_playlist().map(|playlist| playlist.name.as_str())
+}
+
+#[tokio::test]
+async fn starts_on_first_playlist() {
+    let app = app_with_playlists().await;
+    assert_eq!(app.current_page(), Page::PlaylistList);
+    assert_eq!(selected_name(&app), Some("Morning"));
+}
+
+#[tokio::test]
+async fn down_and_up_move_selection() {
+    let mut app = app_with_playlists().await;
+    press(&mut app, KeyCode::Down).await;
+    assert_eq!(selected_name(&app), Some("Focus"));
+    press(&mut app, KeyCode::Char('j')).await;
+    assert_eq!(selected_name(&app), Some("Night"));
+    press(&mut app, KeyCode::Up).await;
+    assert_eq!(selected_name(&app), Some("Focus"));
+    press(&mut app, KeyCode::Char('k')).await;
+    assert_eq!(selected_name(&app), Some("Morning"));
+}
+
+#[tokio::test]
+async fn single_steps_wrap_around_at_the_ends() {
+    let mut app = app_with_playlists().await;
+    press(&mut app, KeyCode::Up).await;
+    assert_eq!(selected_name(&app), Some("Night"));
+    press(&mut app, KeyCode::Down).await;
+    assert_eq!(selected_name(&app), Some("Morning"));
+}
+
+#[tokio::test]
+async fn jumps_stop_at_the_ends() {
+    let mut app = app_with_playlists().await;
+    press(&mut app, KeyCode::End).await;
+    assert_eq!(selected_name(&app), Some("Night"));
+    press(&mut app, KeyCode::PageDown).await;
+    assert_eq!(selected_name(&app), Some("Night"));
+
+    press(&mut app, KeyCode::Home).await;
+    assert_eq!(selected_name(&app), Some("Morning"));
+    press(&mut app, KeyCode::PageUp).await;
+    assert_eq!(selected_name(&app), Some("Morning"));
+}
+
+#[tokio::test]
+async fn enter_plays_and_opens_now_playing() {
+    let mut app = app_with_playlists().await;
+    press(&mut app, KeyCode::Down).await;
+    press(&mut app, KeyCode::Enter).await;
+    assert_eq!(app.current_page(), Page::NowPlaying);
+
+    // 戻っても選択は変わらない
+    press(&mut app, KeyCode::Char('[')).await;
+    assert_eq!(app.current_page(), Page::PlaylistList);
+    assert_eq!(selected_name(&app), Some("Focus"));
+}
+
+#[tokio::test]
+async fn q_quits() {
+    let mut app = app_with_playlists().await;
+    assert!(!app.should_exit());
+    press(&mut app, KeyCode::Char('q')).await;
+    assert!(app.should_exit());
+}
+
+#[tokio::test]
+async fn empty_list_keeps_nothing_selected() {
+    let mut app = app_with_playlists_json(r#"{"items":[]}"#.to_string()).await;
+    assert_eq!(app.current_page(), Page::PlaylistList);
+    assert_eq!(selected_name(&app), None);
+    for code in [KeyCode::Up, KeyCode::Down, KeyCode::Home, KeyCode::End, KeyCode::PageDown] {
+        press(&mut app, code).await;
+        assert_eq!(app.current_page(), Page::PlaylistList);
+        assert_eq!(selected_name(&app), None);
+    }
+}
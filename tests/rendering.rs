@@ -0,0 +1,176 @@
+//! App をバッファに描画して、表示位置や小さな枠での描画を確かめる
+//!
+//! 描画そのものは API を呼ばない。ページを開くのに必要な API だけ tiny_http のスタブで返す。
+#![cfg(feature = "tui")]
+
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{buffer::Buffer, layout::Rect, widgets::Widget};
+use rs_pod::action::Action;
+use rs_pod::app::{App, Page};
+use rs_pod::config::Config;
+use rs_pod::{SpotifyClient, SpotifyPlayer};
+use std::thread;
+use tiny_http::{Header, Response, Server};
+
+/// 再生画面の曲名の行（余白・タイトル・区切り線・空行 3 行の下）
+const TRACK_NAME_ROW: u16 = 6;
+
+fn player_with_track(name: &str) -> SpotifyPlayer {
+    let json = format!(
+        r#"{{
+            "is_playing": true,
+            "progress_ms": 1000,
+            "item": {{ "id": "track0", "uri": "spotify:track:track0", "name": "{}", "duration_ms": 200000, "artists": [{{ "name": "YOASOBI" }}] }},
+            "device": {{ "id": "device0", "name": "Test device", "is_active": true, "volume_percent": 50, "supports_volume": true }}
+        }}"#,
+        name
+    );
+    serde_json::from_str(&json).unwrap()
+}
+
+async fn app_playing(name: &str) -> App {
+    let mut client = SpotifyClient::new(reqwest::Client::new(), &"test-token".to_string())
+        .with_base_url("http://127.0.0.1:9".to_string());
+    client.spotify_player = player_with_track(name);
+    let config = Config {
+        resume_episodes: false,
+        ..Config::default()
+    };
+    let mut app = App::with_client(client, config);
+    app.apply_action(Action::OpenNowPlaying).await;
+    app
+}
+
+fn render(app: &mut App, area: Rect) -> Buffer {
+    let mut buf = Buffer::empty(area);
+    app.render(area, &mut buf);
+    buf
+}
+
+/// 行の中で symbol が最初に現れる列
+fn column_of(buf: &Buffer, row: u16, symbol: &str) -> Option<u16> {
+    (0..buf.area.width).find(|&x| buf[(x, row)].symbol() == symbol)
+}
+
+#[tokio::test]
+async fn wide_track_name_is_centered_by_display_width() {
+    let mut app = app_playing("夜に駆ける").await;
+    // 5 文字だが表示幅は 10（奇数の余りは右側に付く）
+    for (width, start) in [(40, 15), (41, 15), (21, 5), (20, 5)] {
+        let buf = render(&mut app, Rect::new(0, 0, width, 24));
+        assert_eq!(column_of(&buf, TRACK_NAME_ROW, "夜"), Some(start), "width {}", width);
+        assert_eq!(column_of(&buf, TRACK_NAME_ROW, "る"), Some(start + 8), "width {}", width);
+    }
+}
+
+const TRACK_JSON: &str = r#"{ "id": "track0", "uri": "spotify:track:track0", "name": "Morning Light", "duration_ms": 215000,
+    "artists": [{ "name": "First Artist" }, { "name": "Second Artist" }],
+    "album": { "id": "album0", "name": "Dawn", "images": [] } }"#;
+const ALBUM_JSON: &str = r#"{ "id": "album0", "name": "Dawn", "artists": [{ "name": "First Artist" }], "images": [] }"#;
+const PLAYLIST_JSON: &str =
+    r#"{ "id": "playlist0", "name": "Morning", "tracks": { "total": 1 }, "images": [], "owner": { "id": "me" } }"#;
+const DEVICE_JSON: &str =
+    r#"{ "id": "device0", "name": "Test device", "is_active": true, "volume_percent": 50, "supports_volume": true }"#;
+
+/// ページを開くのに使う API に 1 件ずつ返し、それ以外（再生など）は 204 を返すスタブ
+fn spawn_stub_api() -> String {
+    let server = Server::http("127.0.0.1:0").expect("failed to start stub server");
+    let base_url = format!("http://{}", server.server_addr());
+    thread::spawn(move || {
+        for request in server.incoming_requests() {
+            let path = request.url().split('?').next().unwrap_or_default().to_string();
+            let body = match path.as_str() {
+                "/v1/me" => r#"{ "id": "me", "display_name": "Me", "product": "premium" }"#.to_string(),
+                "/v1/me/playlists" => format!(r#"{{ "items": [{}] }}"#, PLAYLIST_JSON),
+                "/v1/playlists/playlist0" => PLAYLIST_JSON.to_string(),
+                "/v1/playlists/playlist0/tracks" => format!(r#"{{ "items": [{{ "track": {} }}] }}"#, TRACK_JSON),
+                "/v1/playlists/playlist0/followers/contains" | "/v1/me/albums/contains" => "[true]".to_string(),
+                "/v1/browse/new-releases" => format!(r#"{{ "albums": {{ "items": [{}] }} }}"#, ALBUM_JSON),
+                "/v1/albums/album0/tracks" => format!(r#"{{ "items": [{}] }}"#, TRACK_JSON),
+                "/v1/me/player/devices" => format!(r#"{{ "devices": [{}] }}"#, DEVICE_JSON),
+                "/v1/me/player" => format!(
+                    r#"{{ "is_playing": true, "progress_ms": 1000, "item": {}, "device": {} }}"#,
+                    TRACK_JSON, DEVICE_JSON
+                ),
+                "/v1/search" => format!(r#"{{ "tracks": {{ "items": [{}] }} }}"#, TRACK_JSON),
+                _ => {
+                    let _ = request.respond(Response::from_string("").with_status_code(204));
+                    continue;
+                }
+            };
+            let header = Header::from_bytes("Content-Type", "application/json").unwrap();
+            let _ = request.respond(Response::from_string(body).with_header(header));
+        }
+    });
+    base_url
+}
+
+async fn app_with_stub() -> App {
+    let mut client =
+        SpotifyClient::new(reqwest::Client::new(), &"test-token".to_string()).with_base_url(spawn_stub_api());
+    client.spotify_player = client.get_current_playback().await.unwrap();
+    let config = Config {
+        resume_episodes: false,
+        ..Config::default()
+    };
+    let mut app = App::with_client(client, config);
+    app.wait_for_playlists().await;
+    app
+}
+
+async fn press(app: &mut App, code: KeyCode) {
+    if let Some(action) = Action::from_key(app.current_page(), KeyEvent::from(code)) {
+        app.apply_action(action).await;
+    }
+}
+
+/// バッファの中身を 1 つの文字列にする（表示されているかの確認用）
+fn text(buf: &Buffer) -> String {
+    buf.content().iter().map(|cell| cell.symbol()).collect()
+}
+
+/// 各ページとモーダルを開くキーの並び、開いたページ、開いたときに見える文字
+const SCREENS: &[(&[KeyCode], Page, &str)] = &[
+    (&[], Page::PlaylistList, "Morning"),
+    (&[KeyCode::Char('?')], Page::PlaylistList, " Help "),
+    (&[KeyCode::Char('c')], Page::PlaylistList, " New playlist "),
+    (&[KeyCode::Char('l')], Page::PlaylistTracks, "Morning Light"),
+    (&[KeyCode::Char('l'), KeyCode::Char('d')], Page::PlaylistTracks, " Remove track "),
+    (&[KeyCode::Char('e')], Page::QueuePreview, "Morning Light"),
+    (&[KeyCode::Char('n')], Page::NewReleases, "Dawn"),
+    (&[KeyCode::Char('n'), KeyCode::Enter], Page::AlbumTracks, "Morning Light"),
+    (&[KeyCode::Char('s')], Page::Search, " Search "),
+    (&[KeyCode::Char('g')], Page::NowPlaying, "Morning Light"),
+    (&[KeyCode::Char('g'), KeyCode::Char('a')], Page::NowPlaying, " Artists "),
+    (&[KeyCode::Char('g'), KeyCode::Char('i')], Page::NowPlaying, " Track Info "),
+    (&[KeyCode::Char('g'), KeyCode::Char('z')], Page::NowPlaying, " Sleep timer (minutes) "),
+    (&[KeyCode::Char('g'), KeyCode::Char('A')], Page::NowPlaying, "Enter:Add"),
+    (&[KeyCode::Char('g'), KeyCode::Char('d')], Page::Devices, "Test device"),
+];
+
+#[tokio::test]
+async fn every_screen_survives_tiny_areas() {
+    for (keys, page, marker) in SCREENS {
+        let mut app = app_with_stub().await;
+        for &code in *keys {
+            press(&mut app, code).await;
+        }
+        assert_eq!(app.current_page(), *page, "after {:?}", keys);
+        // 開けていることを普通の大きさで確かめてから小さい枠に描く
+        let buf = render(&mut app, Rect::new(0, 0, 100, 30));
+        assert!(text(&buf).contains(marker), "{:?} not shown after {:?}", marker, keys);
+
+        for area in [
+            Rect::new(0, 0, 1, 1),
+            Rect::new(0, 0, 0, 10),
+            Rect::new(0, 0, 10, 0),
+            Rect::new(0, 0, 0, 0),
+            Rect::new(0, 0, 2, 1),
+            // 再生画面の縮めた表示
+            Rect::new(0, 0, 3, 4),
+            Rect::new(0, 0, 40, 8),
+        ] {
+            render(&mut app, area);
+        }
+    }
+}
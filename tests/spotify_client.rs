@@ -0,0 +1,288 @@
+//! SpotifyClient の各メソッドが送るリクエストと、レスポンスの読み方
+//!
+//! tiny_http のスタブを立てて `with_base_url` で向き先を変え、届いたリクエストを記録して確かめる。
+
+use rs_pod::api::error::ErrorKind;
+use rs_pod::{PlayOffset, SkipDirection, SpotifyClient};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+use tiny_http::{Header, Method, Response, Server};
+
+/// スタブに届いたリクエスト
+#[derive(Debug, Clone)]
+struct Recorded {
+    method: Method,
+    url: String,
+    authorization: Option<String>,
+    content_type: Option<String>,
+    body: String,
+}
+
+/// スタブが返すレスポンス（ステータスと JSON の本文）。3 つ目の引数はそれまでに届いたリクエストの数
+type Route = fn(&Method, &str, usize) -> (u16, String);
+
+/// route の返すレスポンスを返し、届いたリクエストを記録するスタブ
+fn spawn_stub_api(route: Route) -> (String, Arc<Mutex<Vec<Recorded>>>) {
+    let server = Server::http("127.0.0.1:0").expect("failed to start stub server");
+    let base_url = format!("http://{}", server.server_addr());
+    let recorded = Arc::new(Mutex::new(Vec::new()));
+    let log = Arc::clone(&recorded);
+    thread::spawn(move || {
+        for mut request in server.incoming_requests() {
+            let header = |name: &'static str| {
+                request
+                    .headers()
+                    .iter()
+                    .find(|header| header.field.equiv(name))
+                    .map(|header| header.value.to_string())
+            };
+            let authorization = header("Authorization");
+            let content_type = header("Content-Type");
+            let mut body = String::new();
+            let _ = request.as_reader().read_to_string(&mut body);
+            let mut log = log.lock().unwrap();
+            let (status, response_body) = route(request.method(), request.url(), log.len());
+            log.push(Recorded {
+                method: request.method().clone(),
+                url: request.url().to_string(),
+                authorization,
+                content_type,
+                body,
+            });
+            drop(log);
+            let header = Header::from_bytes("Content-Type", "application/json").unwrap();
+            let mut response = Response::from_string(response_body)
+                .with_status_code(status)
+                .with_header(header);
+            // 429 には 30 秒後まで待つよう返す（クライアントは待たずに返るはず）
+            if status == 429 {
+                response.add_header(Header::from_bytes("Retry-After", "30").unwrap());
+            }
+            let _ = request.respond(response);
+        }
+    });
+    (base_url, recorded)
+}
+
+fn client(base_url: String) -> SpotifyClient {
+    SpotifyClient::new(reqwest::Client::new(), &"test-token".to_string()).with_base_url(base_url)
+}
+
+const PLAYER_JSON: &str = r#"{
+    "is_playing": true,
+    "progress_ms": 42000,
+    "currently_playing_type": "track",
+    "shuffle_state": true,
+    "repeat_state": "context",
+    "device": {
+        "id": "device0",
+        "name": "Desk speaker",
+        "is_active": true,
+        "volume_percent": 65,
+        "supports_volume": true
+    },
+    "context": { "uri": "spotify:playlist:playlist0" },
+    "item": {
+        "id": "track0",
+        "uri": "spotify:track:track0",
+        "name": "Morning Light",
+        "artists": [{ "name": "First Artist" }, { "name": "Second Artist" }],
+        "duration_ms": 215000,
+        "explicit": false,
+        "album": { "id": "album0", "name": "Sunrise", "images": [] },
+        "popularity": 57,
+        "track_number": 3
+    }
+}"#;
+
+#[tokio::test]
+async fn current_playback_parses_full_player() {
+    let (base_url, recorded) = spawn_stub_api(|_, _, _| (200, PLAYER_JSON.to_string()));
+    let player = client(base_url).get_current_playback().await.unwrap();
+
+    assert!(player.is_playing);
+    assert_eq!(player.progress_ms, Some(42000));
+    assert!(player.shuffle_state);
+    let device = player.device.expect("device");
+    assert_eq!(device.id.as_deref(), Some("device0"));
+    assert_eq!(device.volume_percent, Some(65));
+    assert_eq!(player.context.map(|context| context.uri).as_deref(), Some("spotify:playlist:playlist0"));
+    let track = player.item.expect("item");
+    assert_eq!(track.name, "Morning Light");
+    assert_eq!(track.duration_ms, 215000);
+    assert_eq!(track.artist_names(), "First Artist, Second Artist");
+    assert_eq!(track.album.name, "Sunrise");
+
+    let requests = recorded.lock().unwrap();
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0].method, Method::Get);
+    assert!(requests[0].url.starts_with("/v1/me/player?"));
+    assert_eq!(requests[0].authorization.as_deref(), Some("Bearer test-token"));
+}
+
+#[tokio::test]
+async fn current_playback_without_content_is_empty() {
+    let (base_url, _) = spawn_stub_api(|_, _, _| (204, String::new()));
+    let player = client(base_url).get_current_playback().await.unwrap();
+
+    assert!(!player.is_playing);
+    assert!(player.item.is_none());
+    assert!(player.device.is_none());
+}
+
+#[tokio::test]
+async fn current_playback_server_error_fails_after_retries() {
+    let (base_url, recorded) = spawn_stub_api(|_, _, _| {
+        (500, r#"{"error":{"status":500,"message":"Server error"}}"#.to_string())
+    });
+    let error = client(base_url).get_current_playback().await.unwrap_err();
+
+    assert!(error.to_string().contains("500"), "unexpected error: {}", error);
+    // 最初の 1 回と再試行 3 回
+    assert_eq!(recorded.lock().unwrap().len(), 4);
+}
+
+#[tokio::test]
+async fn skip_track_posts_and_refreshes_player() {
+    let (base_url, recorded) = spawn_stub_api(|method, url, _| match (method, url) {
+        (Method::Post, "/v1/me/player/next") => (204, String::new()),
+        (Method::Get, url) if url.starts_with("/v1/me/player?") => (200, PLAYER_JSON.to_string()),
+        _ => (404, String::new()),
+    });
+    let mut client = client(base_url);
+    client.skip_track(SkipDirection::Next).await.unwrap();
+
+    let requests = recorded.lock().unwrap();
+    assert_eq!(requests[0].method, Method::Post);
+    assert_eq!(requests[0].url, "/v1/me/player/next");
+    assert_eq!(requests[0].authorization.as_deref(), Some("Bearer test-token"));
+    assert!(requests[0].body.is_empty());
+    // スキップ後の再生状態を取り直している
+    assert_eq!(requests.len(), 2);
+    assert_eq!(
+        client.spotify_player.item.as_ref().map(|track| track.name.as_str()),
+        Some("Morning Light")
+    );
+}
+
+#[tokio::test]
+async fn skip_track_previous_uses_previous_endpoint() {
+    let (base_url, recorded) = spawn_stub_api(|_, _, _| (204, String::new()));
+    client(base_url).skip_track(SkipDirection::Previous).await.unwrap();
+
+    let requests = recorded.lock().unwrap();
+    assert_eq!(requests[0].method, Method::Post);
+    assert_eq!(requests[0].url, "/v1/me/player/previous");
+}
+
+#[tokio::test]
+async fn play_playlist_sends_context_and_offset() {
+    let (base_url, recorded) = spawn_stub_api(|_, _, _| (204, String::new()));
+    client(base_url)
+        .play_playlist("playlist0", Some("spotify:track:track3"), Some("device0"))
+        .await
+        .unwrap();
+
+    let requests = recorded.lock().unwrap();
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0].method, Method::Put);
+    assert_eq!(requests[0].url, "/v1/me/player/play?device_id=device0");
+    assert_eq!(requests[0].content_type.as_deref(), Some("application/json"));
+    let body: serde_json::Value = serde_json::from_str(&requests[0].body).unwrap();
+    assert_eq!(
+        body,
+        serde_json::json!({
+            "context_uri": "spotify:playlist:playlist0",
+            "position_ms": 0,
+            "offset": { "uri": "spotify:track:track3" }
+        })
+    );
+}
+
+#[tokio::test]
+async fn play_playlist_starts_from_first_track() {
+    let (base_url, recorded) = spawn_stub_api(|_, _, _| (204, String::new()));
+    client(base_url).play_playlist("playlist0", None, None).await.unwrap();
+
+    let requests = recorded.lock().unwrap();
+    assert_eq!(requests[0].url, "/v1/me/player/play");
+    let body: serde_json::Value = serde_json::from_str(&requests[0].body).unwrap();
+    assert_eq!(body["offset"], serde_json::json!({ "position": 0 }));
+}
+
+#[tokio::test]
+async fn current_playback_records_rate_limit_without_waiting() {
+    let (base_url, recorded) = spawn_stub_api(|_, _, seen| match seen {
+        0 => (429, r#"{"error":{"status":429,"message":"API rate limit exceeded"}}"#.to_string()),
+        _ => (200, PLAYER_JSON.to_string()),
+    });
+    let client = client(base_url);
+    let started = Instant::now();
+    let err = client.get_current_playback().await.unwrap_err();
+
+    assert!(started.elapsed() < Duration::from_secs(5));
+    assert_eq!(ErrorKind::classify(err.as_ref()), ErrorKind::RateLimited);
+    assert_eq!(recorded.lock().unwrap().len(), 1);
+    // 再開時刻は Retry-After の分だけ先になる
+    let until = client.rate_limited_until().unwrap();
+    assert!(until > Instant::now() + Duration::from_secs(20));
+
+    // クライアント自体は止めない（いつ取り直すかは呼び出し側が決める）
+    let player = client.get_current_playback().await.unwrap();
+    assert_eq!(player.item.map(|track| track.name).as_deref(), Some("Morning Light"));
+}
+
+#[tokio::test]
+async fn play_context_accepts_playlist_album_and_artist() {
+    let (base_url, recorded) = spawn_stub_api(|_, _, _| (204, String::new()));
+    let client = client(base_url);
+    for uri in ["spotify:playlist:playlist0", "spotify:album:album0", "spotify:artist:artist0"] {
+        client.play_context(uri, None, 0, None).await.unwrap();
+    }
+    client
+        .play_context("spotify:album:album0", Some(PlayOffset::Uri("spotify:track:track0")), 0, None)
+        .await
+        .unwrap();
+
+    let requests = recorded.lock().unwrap();
+    assert_eq!(requests.len(), 4);
+    let bodies: Vec<serde_json::Value> = requests.iter().map(|r| serde_json::from_str(&r.body).unwrap()).collect();
+    assert_eq!(bodies[0]["context_uri"], "spotify:playlist:playlist0");
+    assert_eq!(bodies[1]["context_uri"], "spotify:album:album0");
+    assert_eq!(bodies[2]["context_uri"], "spotify:artist:artist0");
+    assert_eq!(bodies[3]["offset"]["uri"], "spotify:track:track0");
+}
+
+#[tokio::test]
+async fn play_context_rejects_artist_with_offset() {
+    let (base_url, recorded) = spawn_stub_api(|_, _, _| (204, String::new()));
+    let client = client(base_url);
+    for offset in [PlayOffset::Position(2), PlayOffset::Uri("spotify:track:track0")] {
+        let error = client
+            .play_context("spotify:artist:artist0", Some(offset), 0, None)
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("offset"), "unexpected error: {}", error);
+    }
+    assert!(recorded.lock().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn play_context_rejects_unsupported_uris() {
+    let (base_url, recorded) = spawn_stub_api(|_, _, _| (204, String::new()));
+    let client = client(base_url);
+    for uri in [
+        "spotify:track:track0",
+        "spotify:show:show0",
+        "spotify:playlist:",
+        "spotify:album",
+        "spotify:playlist:playlist0:extra",
+        "https://open.spotify.com/playlist/playlist0",
+        "",
+    ] {
+        let error = client.play_context(uri, None, 0, None).await.unwrap_err();
+        assert!(error.to_string().starts_with("Unsupported context URI"), "{}: {}", uri, error);
+    }
+    assert!(recorded.lock().unwrap().is_empty());
+}